@@ -0,0 +1,49 @@
+#!/usr/bin/env bash
+# Compares the benchmarks against a stored baseline and fails on a regression.
+#
+#   benches/compare.sh save    record the baseline into benches/baseline
+#   benches/compare.sh         run the benchmarks against it
+#
+# Extra arguments go to criterion, e.g. `benches/compare.sh -- --quick`.
+# Timings depend on the machine, so record the baseline where you compare.
+set -euo pipefail
+cd "$(dirname "$0")/.."
+
+stored=benches/baseline
+criterion=target/criterion
+mode=check
+case "${1:-}" in
+save | check) mode=$1 && shift ;;
+esac
+if [ "${1:-}" = "--" ]; then
+    shift
+fi
+
+case "$mode" in
+save)
+    cargo bench --bench detectors -- --save-baseline stored "$@"
+    rm -rf "$stored"
+    (cd "$criterion" && find . -type d -name stored) | while read -r dir; do
+        mkdir -p "$stored/$dir"
+        cp "$criterion/$dir"/*.json "$stored/$dir"
+    done
+    echo "baseline saved to $stored"
+    ;;
+check)
+    if [ ! -d "$stored" ]; then
+        echo "no baseline in $stored, run '$0 save' first" >&2
+        exit 1
+    fi
+    mkdir -p "$criterion"
+    cp -R "$stored"/. "$criterion"
+    cargo bench --bench detectors -- --baseline stored "$@" | tee target/bench_compare.txt
+    if grep -q "Performance has regressed" target/bench_compare.txt; then
+        echo "benchmarks regressed against $stored" >&2
+        exit 1
+    fi
+    ;;
+*)
+    echo "usage: $0 [save|check] [-- criterion args]" >&2
+    exit 2
+    ;;
+esac
@@ -0,0 +1,126 @@
+// Benchmarks for the hand detectors, deck shuffling and simulation throughput.
+//
+// Record a baseline before changing the evaluator and compare against it after:
+//   benches/compare.sh save
+//   benches/compare.sh
+// The baseline is kept in benches/baseline, so it survives `cargo clean`, and
+// the comparison exits non-zero when criterion reports a regression.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use monte::{
+    card_set::CardSet,
+    deck::Deck,
+    hand::Hand,
+    lookup::best_hand,
+    seed::Seed,
+    simulation::{simulate, SimConfig},
+    valid_hands::ValidHands,
+};
+
+const HAND_SIZE: usize = 8;
+const HANDS: usize = 256;
+
+// A fixed set of random hands so every run measures the same work.
+fn sample_hands() -> Vec<Hand> {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let mut deck = Deck::standard();
+    (0..HANDS)
+        .map(|_| {
+            deck.cards.shuffle(&mut rng);
+            Hand {
+                cards: deck.cards[..HAND_SIZE].to_vec(),
+                size: HAND_SIZE,
+            }
+        })
+        .collect()
+}
+
+fn detectors(c: &mut Criterion) {
+    let hands = sample_hands();
+    let sets: Vec<CardSet> = hands.iter().map(CardSet::from).collect();
+
+    let mut group = c.benchmark_group("detectors");
+    group.throughput(Throughput::Elements(HANDS as u64));
+
+    group.bench_function("has_straight", |b| {
+        b.iter(|| {
+            for hand in &hands {
+                black_box(ValidHands::has_straight(hand.clone()));
+            }
+        })
+    });
+    group.bench_function("detect_flush", |b| {
+        b.iter(|| {
+            for hand in &hands {
+                black_box(ValidHands::detect_flush(hand.clone()));
+            }
+        })
+    });
+    for n in [2, 3, 4] {
+        group.bench_function(format!("has_n_of_a_kind/{n}"), |b| {
+            b.iter(|| {
+                for hand in &hands {
+                    black_box(ValidHands::has_n_of_a_kind(hand.clone(), n));
+                }
+            })
+        });
+    }
+    group.bench_function("card_set/has_straight", |b| {
+        b.iter(|| {
+            for set in &sets {
+                black_box(set.has_straight());
+            }
+        })
+    });
+    group.bench_function("lookup/best_hand", |b| {
+        b.iter(|| {
+            for set in &sets {
+                black_box(best_hand(*set));
+            }
+        })
+    });
+    group.finish();
+}
+
+fn deck(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deck");
+    group.bench_function("standard", |b| b.iter(Deck::standard));
+    group.bench_function("standard_and_shuffle", |b| {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        b.iter(|| {
+            let mut deck = Deck::standard();
+            deck.shuffle(&mut rng);
+            deck
+        })
+    });
+    group.finish();
+}
+
+fn simulation(c: &mut Criterion) {
+    const SAMPLES: usize = 100_000;
+
+    let mut group = c.benchmark_group("simulation");
+    group.throughput(Throughput::Elements(SAMPLES as u64));
+    group.sample_size(10);
+    for threads in [1, 4] {
+        group.bench_function(format!("simulate/{threads}_threads"), |b| {
+            b.iter_batched(
+                || {
+                    let mut config = SimConfig::new(SAMPLES, HAND_SIZE);
+                    config.seed = Seed::Numeric(0);
+                    config.threads = threads;
+                    config
+                },
+                |config| simulate(&config),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, detectors, deck, simulation);
+criterion_main!(benches);
@@ -0,0 +1,735 @@
+//! Balatro's own random number generation, so a game seed such as "7LB2WVPK"
+//! deals what the game would. [`BalatroRng`] keeps the game's keyed streams and
+//! [`crate::round::RoundSetup::deal_balatro`] uses them to shuffle each round.
+//!
+//! Besides shuffles it covers the lucky card rolls, boss selection and the cards
+//! in the shop. The shop pools assume a fully unlocked profile with the default
+//! rates and no vouchers or Jokers (e.g. Showman) that change them.
+
+use std::{
+    collections::{HashMap, HashSet},
+    f64::consts::{E, PI},
+};
+
+use crate::{
+    card::Card,
+    deck::Deck,
+    modifiers::Enhancements,
+    suit::{Ranks, Suits},
+};
+
+/// Port of LuaJIT's math.random: a combined Tausworthe generator (TW223) seeded
+/// from a double. Balatro reseeds this before every random call, so matching it
+/// bit for bit is what makes shuffles line up with the game.
+#[derive(Debug, Clone)]
+pub struct LuaRandom {
+    state: [u64; 4],
+}
+
+impl LuaRandom {
+    pub fn new(seed: f64) -> LuaRandom {
+        let mut rng = LuaRandom { state: [0; 4] };
+        rng.randomseed(seed);
+        rng
+    }
+
+    pub fn randomseed(&mut self, seed: f64) {
+        // 64 - k for each of the four generators, packed one per byte.
+        let mut r: u32 = 0x11090601;
+        let mut d = seed;
+        for i in 0..4 {
+            let m = 1u64 << (r & 255);
+            r >>= 8;
+            d = d * PI + E;
+            let mut u = d.to_bits();
+            if u < m {
+                u += m;
+            }
+            self.state[i] = u;
+        }
+        for _ in 0..10 {
+            self.step();
+        }
+    }
+
+    fn step(&mut self) -> u64 {
+        let mut r = 0;
+        for (i, (k, q, s)) in [(63, 31, 18), (58, 19, 28), (55, 24, 7), (47, 21, 8)]
+            .into_iter()
+            .enumerate()
+        {
+            let z = self.state[i];
+            let z = (((z << q) ^ z) >> (k - s)) ^ ((z & (u64::MAX << (64 - k))) << s);
+            r ^= z;
+            self.state[i] = z;
+        }
+        r
+    }
+
+    /// math.random() with no arguments, in [0, 1).
+    pub fn random(&mut self) -> f64 {
+        let bits = (self.step() & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000;
+        f64::from_bits(bits) - 1.
+    }
+
+    /// math.random(min, max), inclusive on both ends.
+    pub fn random_range(&mut self, min: i64, max: i64) -> i64 {
+        (self.random() * (max - min + 1) as f64).floor() as i64 + min
+    }
+}
+
+// Lua's float modulo, a - floor(a / b) * b, which differs from Rust's % for negatives.
+fn lua_mod(a: f64, b: f64) -> f64 {
+    a - (a / b).floor() * b
+}
+
+pub fn pseudohash(s: &str) -> f64 {
+    let mut num = 1.;
+    for (i, byte) in s.bytes().enumerate().rev() {
+        num = lua_mod(
+            (1.1239285023 / num) * byte as f64 * PI + PI * (i + 1) as f64,
+            1.,
+        );
+    }
+    num
+}
+
+/// Balatro's keyed random streams. Each key (e.g. "nr1" for the ante one shuffle)
+/// keeps its own state which advances every time it is drawn from, mixed with the
+/// hash of the run seed, mirroring pseudoseed/pseudorandom in the game's Lua.
+#[derive(Debug, Clone)]
+pub struct BalatroRng {
+    seed: String,
+    hashed_seed: f64,
+    streams: HashMap<String, f64>,
+    lua: LuaRandom,
+    bosses_used: HashMap<&'static str, usize>,
+    items_used: HashSet<&'static str>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ShopCardTypes {
+    Joker,
+    Tarot,
+    Planet,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum JokerRarities {
+    Common,
+    Uncommon,
+    Rare,
+}
+
+/// A card for sale in the shop, by its game key (e.g. "j_joker" or "c_fool").
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ShopCard {
+    pub card_type: ShopCardTypes,
+    pub key: &'static str,
+}
+
+// Every non-legendary Joker in the game's `order`, which is how get_current_pool
+// lists them before picking one.
+const JOKERS: [(&str, JokerRarities); 145] = [
+    ("j_joker", JokerRarities::Common),
+    ("j_greedy_joker", JokerRarities::Common),
+    ("j_lusty_joker", JokerRarities::Common),
+    ("j_wrathful_joker", JokerRarities::Common),
+    ("j_gluttenous_joker", JokerRarities::Common),
+    ("j_jolly", JokerRarities::Common),
+    ("j_zany", JokerRarities::Common),
+    ("j_mad", JokerRarities::Common),
+    ("j_crazy", JokerRarities::Common),
+    ("j_droll", JokerRarities::Common),
+    ("j_sly", JokerRarities::Common),
+    ("j_wily", JokerRarities::Common),
+    ("j_clever", JokerRarities::Common),
+    ("j_devious", JokerRarities::Common),
+    ("j_crafty", JokerRarities::Common),
+    ("j_half", JokerRarities::Common),
+    ("j_stencil", JokerRarities::Uncommon),
+    ("j_four_fingers", JokerRarities::Uncommon),
+    ("j_mime", JokerRarities::Uncommon),
+    ("j_credit_card", JokerRarities::Common),
+    ("j_ceremonial", JokerRarities::Uncommon),
+    ("j_banner", JokerRarities::Common),
+    ("j_mystic_summit", JokerRarities::Common),
+    ("j_marble", JokerRarities::Uncommon),
+    ("j_loyalty_card", JokerRarities::Uncommon),
+    ("j_8_ball", JokerRarities::Common),
+    ("j_misprint", JokerRarities::Common),
+    ("j_dusk", JokerRarities::Uncommon),
+    ("j_raised_fist", JokerRarities::Common),
+    ("j_chaos", JokerRarities::Common),
+    ("j_fibonacci", JokerRarities::Uncommon),
+    ("j_steel_joker", JokerRarities::Uncommon),
+    ("j_scary_face", JokerRarities::Common),
+    ("j_abstract", JokerRarities::Common),
+    ("j_delayed_grat", JokerRarities::Common),
+    ("j_hack", JokerRarities::Uncommon),
+    ("j_pareidolia", JokerRarities::Uncommon),
+    ("j_gros_michel", JokerRarities::Common),
+    ("j_even_steven", JokerRarities::Common),
+    ("j_odd_todd", JokerRarities::Common),
+    ("j_scholar", JokerRarities::Common),
+    ("j_business", JokerRarities::Common),
+    ("j_supernova", JokerRarities::Common),
+    ("j_ride_the_bus", JokerRarities::Common),
+    ("j_space", JokerRarities::Uncommon),
+    ("j_egg", JokerRarities::Common),
+    ("j_burglar", JokerRarities::Uncommon),
+    ("j_blackboard", JokerRarities::Uncommon),
+    ("j_runner", JokerRarities::Common),
+    ("j_ice_cream", JokerRarities::Common),
+    ("j_dna", JokerRarities::Rare),
+    ("j_splash", JokerRarities::Common),
+    ("j_blue_joker", JokerRarities::Common),
+    ("j_sixth_sense", JokerRarities::Uncommon),
+    ("j_constellation", JokerRarities::Uncommon),
+    ("j_hiker", JokerRarities::Uncommon),
+    ("j_faceless", JokerRarities::Common),
+    ("j_green_joker", JokerRarities::Common),
+    ("j_superposition", JokerRarities::Common),
+    ("j_todo_list", JokerRarities::Common),
+    ("j_cavendish", JokerRarities::Common),
+    ("j_card_sharp", JokerRarities::Uncommon),
+    ("j_red_card", JokerRarities::Common),
+    ("j_madness", JokerRarities::Uncommon),
+    ("j_square", JokerRarities::Common),
+    ("j_seance", JokerRarities::Uncommon),
+    ("j_riff_raff", JokerRarities::Common),
+    ("j_vampire", JokerRarities::Uncommon),
+    ("j_shortcut", JokerRarities::Uncommon),
+    ("j_hologram", JokerRarities::Uncommon),
+    ("j_vagabond", JokerRarities::Rare),
+    ("j_baron", JokerRarities::Rare),
+    ("j_cloud_9", JokerRarities::Uncommon),
+    ("j_rocket", JokerRarities::Uncommon),
+    ("j_obelisk", JokerRarities::Rare),
+    ("j_midas_mask", JokerRarities::Uncommon),
+    ("j_luchador", JokerRarities::Uncommon),
+    ("j_photograph", JokerRarities::Common),
+    ("j_gift", JokerRarities::Uncommon),
+    ("j_turtle_bean", JokerRarities::Uncommon),
+    ("j_erosion", JokerRarities::Uncommon),
+    ("j_reserved_parking", JokerRarities::Common),
+    ("j_mail", JokerRarities::Common),
+    ("j_to_the_moon", JokerRarities::Uncommon),
+    ("j_hallucination", JokerRarities::Common),
+    ("j_fortune_teller", JokerRarities::Common),
+    ("j_juggler", JokerRarities::Common),
+    ("j_drunkard", JokerRarities::Common),
+    ("j_stone", JokerRarities::Uncommon),
+    ("j_golden", JokerRarities::Common),
+    ("j_lucky_cat", JokerRarities::Uncommon),
+    ("j_baseball", JokerRarities::Rare),
+    ("j_bull", JokerRarities::Uncommon),
+    ("j_diet_cola", JokerRarities::Uncommon),
+    ("j_trading", JokerRarities::Uncommon),
+    ("j_flash", JokerRarities::Uncommon),
+    ("j_popcorn", JokerRarities::Common),
+    ("j_trousers", JokerRarities::Uncommon),
+    ("j_ancient", JokerRarities::Rare),
+    ("j_ramen", JokerRarities::Uncommon),
+    ("j_walkie_talkie", JokerRarities::Common),
+    ("j_selzer", JokerRarities::Uncommon),
+    ("j_castle", JokerRarities::Uncommon),
+    ("j_smiley", JokerRarities::Common),
+    ("j_campfire", JokerRarities::Rare),
+    ("j_ticket", JokerRarities::Common),
+    ("j_mr_bones", JokerRarities::Uncommon),
+    ("j_acrobat", JokerRarities::Uncommon),
+    ("j_sock_and_buskin", JokerRarities::Uncommon),
+    ("j_swashbuckler", JokerRarities::Common),
+    ("j_troubadour", JokerRarities::Uncommon),
+    ("j_certificate", JokerRarities::Uncommon),
+    ("j_smeared", JokerRarities::Uncommon),
+    ("j_throwback", JokerRarities::Uncommon),
+    ("j_hanging_chad", JokerRarities::Common),
+    ("j_rough_gem", JokerRarities::Uncommon),
+    ("j_bloodstone", JokerRarities::Uncommon),
+    ("j_arrowhead", JokerRarities::Uncommon),
+    ("j_onyx_agate", JokerRarities::Uncommon),
+    ("j_glass", JokerRarities::Uncommon),
+    ("j_ring_master", JokerRarities::Uncommon),
+    ("j_flower_pot", JokerRarities::Uncommon),
+    ("j_blueprint", JokerRarities::Rare),
+    ("j_wee", JokerRarities::Rare),
+    ("j_merry_andy", JokerRarities::Uncommon),
+    ("j_oops", JokerRarities::Uncommon),
+    ("j_idol", JokerRarities::Uncommon),
+    ("j_seeing_double", JokerRarities::Uncommon),
+    ("j_matador", JokerRarities::Uncommon),
+    ("j_hit_the_road", JokerRarities::Rare),
+    ("j_duo", JokerRarities::Rare),
+    ("j_trio", JokerRarities::Rare),
+    ("j_family", JokerRarities::Rare),
+    ("j_order", JokerRarities::Rare),
+    ("j_tribe", JokerRarities::Rare),
+    ("j_stuntman", JokerRarities::Rare),
+    ("j_invisible", JokerRarities::Rare),
+    ("j_brainstorm", JokerRarities::Rare),
+    ("j_satellite", JokerRarities::Uncommon),
+    ("j_shoot_the_moon", JokerRarities::Common),
+    ("j_drivers_license", JokerRarities::Rare),
+    ("j_cartomancer", JokerRarities::Uncommon),
+    ("j_astronomer", JokerRarities::Uncommon),
+    ("j_burnt", JokerRarities::Rare),
+    ("j_bootstraps", JokerRarities::Uncommon),
+];
+
+// Jokers that only show up once the deck holds a card with the enhancement.
+const ENHANCEMENT_GATED: [(&str, Enhancements); 5] = [
+    ("j_glass", Enhancements::Glass),
+    ("j_lucky_cat", Enhancements::Lucky),
+    ("j_steel_joker", Enhancements::Steel),
+    ("j_stone", Enhancements::Stone),
+    ("j_ticket", Enhancements::Gold),
+];
+
+// Cavendish needs Gros Michel to have gone extinct first, which never happens
+// before the shop is reached.
+const POOL_FLAG_GATED: [&str; 1] = ["j_cavendish"];
+
+const TAROTS: [&str; 22] = [
+    "c_fool",
+    "c_magician",
+    "c_high_priestess",
+    "c_empress",
+    "c_emperor",
+    "c_heirophant",
+    "c_lovers",
+    "c_chariot",
+    "c_justice",
+    "c_hermit",
+    "c_wheel_of_fortune",
+    "c_strength",
+    "c_hanged_man",
+    "c_death",
+    "c_temperance",
+    "c_devil",
+    "c_tower",
+    "c_star",
+    "c_moon",
+    "c_sun",
+    "c_judgement",
+    "c_world",
+];
+
+const PLANETS: [&str; 12] = [
+    "c_mercury",
+    "c_venus",
+    "c_earth",
+    "c_mars",
+    "c_jupiter",
+    "c_saturn",
+    "c_uranus",
+    "c_neptune",
+    "c_pluto",
+    "c_planet_x",
+    "c_ceres",
+    "c_eris",
+];
+
+// Planets for the secret hands stay out of the pool until the hand is played.
+const SOFTLOCKED_PLANETS: [&str; 3] = ["c_planet_x", "c_ceres", "c_eris"];
+
+// Boss blind keys with the minimum ante they can appear in. Showdown bosses only
+// appear on ante 8 (and multiples of it).
+const BOSSES: [(&str, u32); 23] = [
+    ("bl_arm", 2),
+    ("bl_club", 1),
+    ("bl_eye", 3),
+    ("bl_fish", 2),
+    ("bl_flint", 2),
+    ("bl_goad", 1),
+    ("bl_head", 1),
+    ("bl_hook", 1),
+    ("bl_house", 2),
+    ("bl_manacle", 1),
+    ("bl_mark", 2),
+    ("bl_mouth", 2),
+    ("bl_needle", 2),
+    ("bl_ox", 6),
+    ("bl_pillar", 1),
+    ("bl_plant", 4),
+    ("bl_psychic", 1),
+    ("bl_serpent", 5),
+    ("bl_tooth", 3),
+    ("bl_wall", 2),
+    ("bl_water", 2),
+    ("bl_wheel", 2),
+    ("bl_window", 1),
+];
+
+const SHOWDOWN_BOSSES: [&str; 5] = [
+    "bl_final_acorn",
+    "bl_final_bell",
+    "bl_final_heart",
+    "bl_final_leaf",
+    "bl_final_vessel",
+];
+
+impl BalatroRng {
+    pub fn new(seed: &str) -> BalatroRng {
+        BalatroRng {
+            seed: seed.to_string(),
+            hashed_seed: pseudohash(seed),
+            streams: HashMap::new(),
+            lua: LuaRandom::new(0.),
+            bosses_used: HashMap::new(),
+            items_used: HashSet::new(),
+        }
+    }
+
+    pub fn pseudoseed(&mut self, key: &str) -> f64 {
+        let state = self
+            .streams
+            .entry(key.to_string())
+            .or_insert_with(|| pseudohash(&format!("{key}{}", self.seed)));
+
+        // The game rounds through string.format("%.13f") on every advance.
+        let advanced = lua_mod(2.134453429141 + *state * 1.72431234, 1.);
+        *state = format!("{advanced:.13}").parse::<f64>().unwrap().abs();
+
+        (*state + self.hashed_seed) / 2.
+    }
+
+    pub fn pseudorandom(&mut self, key: &str) -> f64 {
+        let seed = self.pseudoseed(key);
+        self.lua.randomseed(seed);
+        self.lua.random()
+    }
+
+    pub fn pseudorandom_range(&mut self, key: &str, min: i64, max: i64) -> i64 {
+        let seed = self.pseudoseed(key);
+        self.lua.randomseed(seed);
+        self.lua.random_range(min, max)
+    }
+
+    /// Fisher-Yates from the back, as in the game's pseudoshuffle. Callers are
+    /// responsible for putting `items` into the game's canonical order first.
+    pub fn pseudoshuffle<T>(&mut self, items: &mut [T], key: &str) {
+        let seed = self.pseudoseed(key);
+        self.lua.randomseed(seed);
+        for i in (1..items.len()).rev() {
+            let j = self.lua.random_range(1, i as i64 + 1) as usize - 1;
+            items.swap(i, j);
+        }
+    }
+
+    /// `items` must already be sorted the way the game sorts the table keys.
+    pub fn pseudorandom_element<'a, T>(&mut self, items: &'a [T], key: &str) -> &'a T {
+        let seed = self.pseudoseed(key);
+        self.lua.randomseed(seed);
+        &items[self.lua.random_range(1, items.len() as i64) as usize - 1]
+    }
+
+    /// Shuffles the deck the way a new round does ("nr" plus the ante). The deck is
+    /// first put back into the order the game created the cards in, so the result
+    /// only depends on which cards are in the deck. The top card is the last one,
+    /// matching Deck::deal_n_cards.
+    pub fn shuffle_deck(&mut self, deck: &mut Deck, ante: u32) {
+        deck.cards.sort_by_key(balatro_sort_key);
+        self.pseudoshuffle(&mut deck.cards, &format!("nr{ante}"));
+    }
+
+    pub fn lucky_mult(&mut self) -> bool {
+        self.pseudorandom("lucky_mult") < 1. / 5.
+    }
+
+    pub fn lucky_money(&mut self) -> bool {
+        self.pseudorandom("lucky_money") < 1. / 15.
+    }
+
+    pub fn next_boss(&mut self, ante: u32) -> &'static str {
+        let mut eligible: Vec<&'static str> = if ante >= 2 && ante.is_multiple_of(8) {
+            SHOWDOWN_BOSSES.to_vec()
+        } else {
+            BOSSES
+                .iter()
+                .filter(|(_, min_ante)| *min_ante <= ante)
+                .map(|(key, _)| *key)
+                .collect()
+        };
+
+        let least_used = eligible
+            .iter()
+            .map(|key| self.bosses_used.get(key).copied().unwrap_or(0))
+            .min()
+            .unwrap_or(0);
+        eligible.retain(|key| self.bosses_used.get(key).copied().unwrap_or(0) == least_used);
+
+        let boss = *self.pseudorandom_element(&eligible, "boss");
+        *self.bosses_used.entry(boss).or_insert(0) += 1;
+        boss
+    }
+
+    /// Rolls the slot type for a shop card with the default rates (20 Joker, 4 Tarot, 4 Planet).
+    pub fn shop_card_type(&mut self, ante: u32) -> ShopCardTypes {
+        let polled = self.pseudorandom(&format!("cdt{ante}")) * 28.;
+        if polled <= 20. {
+            ShopCardTypes::Joker
+        } else if polled <= 24. {
+            ShopCardTypes::Tarot
+        } else {
+            ShopCardTypes::Planet
+        }
+    }
+
+    pub fn shop_joker_rarity(&mut self, ante: u32) -> JokerRarities {
+        let rarity = self.pseudorandom(&format!("rarity{ante}sho"));
+        if rarity > 0.95 {
+            JokerRarities::Rare
+        } else if rarity > 0.7 {
+            JokerRarities::Uncommon
+        } else {
+            JokerRarities::Common
+        }
+    }
+
+    /// Rolls a whole shop slot: its type, then the card from that type's pool.
+    pub fn shop_card(&mut self, ante: u32, deck: &Deck) -> ShopCard {
+        let card_type = self.shop_card_type(ante);
+        let key = match card_type {
+            ShopCardTypes::Joker => {
+                let rarity = self.shop_joker_rarity(ante);
+                self.shop_joker(rarity, ante, deck)
+            }
+            ShopCardTypes::Tarot => self.shop_tarot(ante),
+            ShopCardTypes::Planet => self.shop_planet(ante),
+        };
+        ShopCard { card_type, key }
+    }
+
+    pub fn shop_joker(&mut self, rarity: JokerRarities, ante: u32, deck: &Deck) -> &'static str {
+        let pool = joker_pool(rarity, deck, &self.items_used);
+        let rarity = match rarity {
+            JokerRarities::Common => 1,
+            JokerRarities::Uncommon => 2,
+            JokerRarities::Rare => 3,
+        };
+        self.pick_from_pool(&pool, &format!("Joker{rarity}sho{ante}"), "j_joker")
+    }
+
+    pub fn shop_tarot(&mut self, ante: u32) -> &'static str {
+        let pool = consumable_pool(&TAROTS, &[], &self.items_used);
+        self.pick_from_pool(&pool, &format!("Tarotsho{ante}"), "c_strength")
+    }
+
+    pub fn shop_planet(&mut self, ante: u32) -> &'static str {
+        let pool = consumable_pool(&PLANETS, &SOFTLOCKED_PLANETS, &self.items_used);
+        self.pick_from_pool(&pool, &format!("Planetsho{ante}"), "c_pluto")
+    }
+
+    // Unavailable entries keep their place in the pool (the game marks them
+    // "UNAVAILABLE"), so landing on one resamples with "_resample2", "_resample3"
+    // and so on. An empty pool offers only the fallback. Whatever is picked is
+    // used and won't be offered again.
+    fn pick_from_pool(
+        &mut self,
+        pool: &[Option<&'static str>],
+        key: &str,
+        fallback: &'static str,
+    ) -> &'static str {
+        let fallback_pool = [Some(fallback)];
+        let pool = if pool.iter().all(Option::is_none) {
+            &fallback_pool[..]
+        } else {
+            pool
+        };
+
+        let mut picked = *self.pseudorandom_element(pool, key);
+        let mut resample = 1;
+        while picked.is_none() {
+            resample += 1;
+            picked = *self.pseudorandom_element(pool, &format!("{key}_resample{resample}"));
+        }
+        let picked = picked.unwrap();
+        self.items_used.insert(picked);
+        picked
+    }
+}
+
+fn joker_pool(
+    rarity: JokerRarities,
+    deck: &Deck,
+    used: &HashSet<&'static str>,
+) -> Vec<Option<&'static str>> {
+    JOKERS
+        .iter()
+        .filter(|(_, joker_rarity)| *joker_rarity == rarity)
+        .map(|&(key, _)| {
+            let gated = match ENHANCEMENT_GATED.iter().find(|(gated, _)| *gated == key) {
+                Some(&(_, enhancement)) => !deck
+                    .cards
+                    .iter()
+                    .any(|card| card.enhancement == Some(enhancement)),
+                None => POOL_FLAG_GATED.contains(&key),
+            };
+            (!gated && !used.contains(key)).then_some(key)
+        })
+        .collect()
+}
+
+fn consumable_pool(
+    cards: &[&'static str],
+    softlocked: &[&str],
+    used: &HashSet<&'static str>,
+) -> Vec<Option<&'static str>> {
+    cards
+        .iter()
+        .map(|&key| (!softlocked.contains(&key) && !used.contains(key)).then_some(key))
+        .collect()
+}
+
+// The game creates the starting deck sorted by suit letter then rank character
+// ("C2" < "CA" < "CT" < "D2" ...), which fixes each card's sort_id.
+fn balatro_sort_key(card: &Card) -> (u8, u8) {
+    let suit = match card.suit {
+        Suits::Spades => b'S',
+        Suits::Hearts => b'H',
+        Suits::Clubs => b'C',
+        Suits::Diamonds => b'D',
+        Suits::Blank => 0,
+    };
+    let rank = match card.rank {
+        Ranks::Ace => b'A',
+        Ranks::King => b'K',
+        Ranks::Queen => b'Q',
+        Ranks::Jack => b'J',
+        Ranks::Ten => b'T',
+        Ranks::Blank => 0,
+        _ => b'0' + card.value as u8,
+    };
+    (suit, rank)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{card::Card, deck::Deck, modifiers::Enhancements};
+
+    use super::{
+        joker_pool, pseudohash, BalatroRng, JokerRarities, LuaRandom, ShopCard, ShopCardTypes,
+        JOKERS,
+    };
+
+    // Reference values below were produced by running the game's Lua functions under LuaJIT 2.1.
+
+    #[test]
+    fn pseudohash_matches_game() {
+        assert_eq!(pseudohash("7LB2WVPK"), 0.17691054639954018);
+        assert_eq!(pseudohash("nr17LB2WVPK"), 0.830_436_858_892_312_6);
+    }
+
+    #[test]
+    fn lua_random_matches_luajit() {
+        let mut rng = LuaRandom::new(0.5);
+        assert_eq!(rng.random(), 0.09657393438653461);
+        assert_eq!(rng.random(), 0.962_269_457_706_84);
+
+        let mut rng = LuaRandom::new(12345.);
+        assert_eq!(rng.random_range(1, 10), 4);
+        assert_eq!(rng.random_range(3, 7), 4);
+    }
+
+    #[test]
+    fn keyed_streams_match_game() {
+        let mut rng = BalatroRng::new("7LB2WVPK");
+        assert_eq!(rng.pseudoseed("nr1"), 0.371_648_249_459_720_1);
+        assert_eq!(rng.pseudoseed("nr1"), 0.143_995_131_336_620_1);
+        assert_eq!(rng.pseudorandom("lucky_mult"), 0.436_175_083_887_094_8);
+        assert_eq!(rng.pseudorandom_range("boss", 1, 20), 1);
+
+        let mut items: Vec<usize> = (1..=52).collect();
+        rng.pseudoshuffle(&mut items, "nr2");
+        assert_eq!(
+            items,
+            vec![
+                23, 30, 2, 1, 28, 13, 16, 14, 27, 42, 11, 15, 4, 20, 21, 33, 24, 34, 22, 35, 51,
+                32, 8, 49, 39, 46, 50, 26, 41, 37, 17, 19, 45, 40, 9, 10, 5, 48, 43, 52, 6, 31, 44,
+                12, 47, 29, 3, 25, 7, 18, 36, 38
+            ]
+        );
+    }
+
+    #[test]
+    fn deck_shuffle_ignores_starting_order() {
+        let mut first = Deck::standard();
+        BalatroRng::new("7LB2WVPK").shuffle_deck(&mut first, 1);
+
+        let mut second = Deck::standard();
+        second.cards.reverse();
+        BalatroRng::new("7LB2WVPK").shuffle_deck(&mut second, 1);
+
+        assert_eq!(first.cards, second.cards);
+    }
+
+    #[test]
+    fn bosses_respect_minimum_ante_and_do_not_repeat() {
+        let mut rng = BalatroRng::new("7LB2WVPK");
+        let first = rng.next_boss(1);
+        assert!(!["bl_ox", "bl_plant", "bl_serpent"].contains(&first));
+        assert!(rng.next_boss(8).starts_with("bl_final_"));
+
+        let seen: Vec<_> = (0..9).map(|_| rng.next_boss(1)).collect();
+        assert!(!seen.contains(&first));
+    }
+    #[test]
+    fn joker_table_matches_game_counts() {
+        let count = |rarity| JOKERS.iter().filter(|(_, r)| *r == rarity).count();
+        assert_eq!(count(JokerRarities::Common), 61);
+        assert_eq!(count(JokerRarities::Uncommon), 64);
+        assert_eq!(count(JokerRarities::Rare), 20);
+    }
+
+    // Pinned from this port; LuaJIT can't run the game's shop code on its own,
+    // so unlike the values above these aren't taken from the game.
+    #[test]
+    fn first_shop_for_known_seed() {
+        let mut rng = BalatroRng::new("7LB2WVPK");
+        let deck = Deck::standard();
+        assert_eq!(
+            [rng.shop_card(1, &deck), rng.shop_card(1, &deck)],
+            [
+                ShopCard {
+                    card_type: ShopCardTypes::Planet,
+                    key: "c_venus"
+                },
+                ShopCard {
+                    card_type: ShopCardTypes::Joker,
+                    key: "j_walkie_talkie"
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn shop_skips_gated_and_used_cards() {
+        let mut rng = BalatroRng::new("7LB2WVPK");
+        let deck = Deck::standard();
+        let keys: Vec<_> = (0..40).map(|_| rng.shop_card(1, &deck).key).collect();
+
+        for gated in ["j_stone", "j_glass", "j_cavendish", "c_planet_x", "c_eris"] {
+            assert!(!keys.contains(&gated));
+        }
+        // Only the Planets run out this soon, after which the pool falls back to Pluto.
+        let rest: Vec<_> = keys.iter().filter(|key| **key != "c_pluto").collect();
+        assert_eq!(rest.iter().collect::<HashSet<_>>().len(), rest.len());
+    }
+
+    #[test]
+    fn enhanced_cards_open_gated_jokers() {
+        let used = HashSet::new();
+        let mut deck = Deck::standard();
+        assert!(!joker_pool(JokerRarities::Uncommon, &deck, &used).contains(&Some("j_stone")));
+
+        deck.cards.push(Card {
+            enhancement: Some(Enhancements::Stone),
+            ..deck.cards[0]
+        });
+        assert!(joker_pool(JokerRarities::Uncommon, &deck, &used).contains(&Some("j_stone")));
+    }
+}
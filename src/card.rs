@@ -1,7 +1,12 @@
-use std::fmt::Display;
+use std::{error::Error, fmt, fmt::Display, str::FromStr};
 
-use crate::suit::{Ranks, Suits};
+use crate::{
+    modifiers::{Editions, Enhancements, Seals},
+    suit::{Ranks, Suits},
+};
 
+/// A playing card. `value` is 2-14 with the Ace high; `alt_value` holds the
+/// Ace-low value 1 for Aces and matches `value` otherwise.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
 pub struct Card {
     pub suit: Suits,
@@ -9,6 +14,9 @@ pub struct Card {
     pub value: usize,
     // alt_value for representing Ace-Low ordering.
     pub alt_value: usize,
+    pub enhancement: Option<Enhancements>,
+    pub edition: Option<Editions>,
+    pub seal: Option<Seals>,
 }
 
 impl Display for Card {
@@ -17,6 +25,86 @@ impl Display for Card {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCardError(String);
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid card {:?}: expected a rank (2-10, T, J, Q, K, A), a suit (S, H, C, D or \
+             ♠ ♥ ♣ ♦) and optional +enhancement, +edition and +seal suffixes",
+            self.0
+        )
+    }
+}
+
+impl Error for ParseCardError {}
+
+// Compact notation such as "AS", "10h", "Td" or "Q♥", case-insensitive, with
+// optional modifiers after the card: "KH+glass+foil+redseal". Each kind of
+// modifier may appear once, in any order. A "?" rank or suit reads a blank card
+// back from to_notation.
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let err = || ParseCardError(s.to_string());
+
+        let mut parts = s.split('+');
+        let base = parts.next().unwrap_or_default();
+        let split = base.char_indices().last().ok_or_else(err)?.0;
+        let (rank, suit) = base.split_at(split);
+        let suit = match suit.to_uppercase().as_str() {
+            "S" | "♠" | "♤" => Suits::Spades,
+            "H" | "♥" | "♡" => Suits::Hearts,
+            "C" | "♣" | "♧" => Suits::Clubs,
+            "D" | "♦" | "♢" => Suits::Diamonds,
+            "?" => Suits::Blank,
+            _ => return Err(err()),
+        };
+        let value = match rank.to_ascii_uppercase().as_str() {
+            "A" => 14,
+            "K" => 13,
+            "Q" => 12,
+            "J" => 11,
+            "T" => 10,
+            "?" => 0,
+            number => match number.parse() {
+                Ok(value @ 2..=10) => value,
+                _ => return Err(err()),
+            },
+        };
+
+        let mut card = match value {
+            0 => Card::new(suit, Ranks::Blank, 0),
+            _ => Card::from_card_value(value, Some(suit)),
+        };
+        for suffix in parts {
+            let suffix = suffix.to_ascii_lowercase();
+            let find = |name: &str| name == suffix;
+            if let Some(enhancement) = Enhancements::ALL.into_iter().find(|e| find(e.to_str())) {
+                if card.enhancement.replace(enhancement).is_some() {
+                    return Err(err());
+                }
+            } else if let Some(edition) = Editions::from_name(&suffix) {
+                if card.edition.replace(edition).is_some() {
+                    return Err(err());
+                }
+            } else if let Some(seal) = Seals::ALL.into_iter().find(|e| find(e.to_str())) {
+                if card.seal.replace(seal).is_some() {
+                    return Err(err());
+                }
+            } else {
+                return Err(err());
+            }
+        }
+
+        Ok(card)
+    }
+}
+
 impl Card {
     pub fn blank() -> Card {
         Card {
@@ -24,6 +112,9 @@ impl Card {
             rank: Ranks::Blank,
             value: Ranks::Blank.get_value(false),
             alt_value: 0,
+            enhancement: None,
+            edition: None,
+            seal: None,
         }
     }
 
@@ -40,9 +131,13 @@ impl Card {
             rank,
             value,
             alt_value,
+            enhancement: None,
+            edition: None,
+            seal: None,
         }
     }
 
+    /// Builds a card from its value (1 or 14 for an Ace), defaulting to Spades.
     pub fn from_card_value(card_value: usize, mut suit: Option<Suits>) -> Self {
         if suit.is_none() {
             suit = Some(Suits::Spades);
@@ -59,14 +154,141 @@ impl Card {
     }
 
     pub fn determine_suit(i: i32) -> Suits {
-        let suit: Suits;
         match i % 4 {
-            0 => suit = Suits::Spades,
-            1 => suit = Suits::Hearts,
-            2 => suit = Suits::Clubs,
-            3 => suit = Suits::Diamonds,
+            0 => Suits::Spades,
+            1 => Suits::Hearts,
+            2 => Suits::Clubs,
+            3 => Suits::Diamonds,
             _ => panic!(),
         }
-        suit
+    }
+
+    /// The card in the notation FromStr reads, e.g. "10H" or "KS+glass+redseal".
+    /// Blank ranks and suits are written as "?".
+    pub fn to_notation(&self) -> String {
+        let rank = match self.rank {
+            Ranks::Ace => "A",
+            Ranks::King => "K",
+            Ranks::Queen => "Q",
+            Ranks::Jack => "J",
+            Ranks::Blank => "?",
+            rank => rank.to_str(),
+        };
+        let suit = match self.suit {
+            Suits::Spades => "S",
+            Suits::Hearts => "H",
+            Suits::Clubs => "C",
+            Suits::Diamonds => "D",
+            Suits::Blank => "?",
+        };
+
+        let mut notation = format!("{rank}{suit}");
+        let suffixes = [
+            self.enhancement.map(Enhancements::to_str),
+            self.edition.map(Editions::to_str),
+            self.seal.map(Seals::to_str),
+        ];
+        for suffix in suffixes.into_iter().flatten() {
+            notation.push('+');
+            notation.push_str(suffix);
+        }
+        notation
+    }
+}
+
+// With the serde feature cards are stored as their notation, e.g. "10H+glass",
+// which is both compact and independent of how Card is laid out.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Card {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_notation())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Card {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let notation = String::deserialize(deserializer)?;
+        notation.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        deck::Deck,
+        modifiers::{Editions, Enhancements, Seals},
+        suit::{Ranks, Suits},
+    };
+
+    use super::Card;
+
+    #[test]
+    fn parses_compact_notation() {
+        assert_eq!("AS".parse(), Ok(Card::from_card_value(14, Some(Suits::Spades))));
+        assert_eq!("10h".parse(), Ok(Card::from_card_value(10, Some(Suits::Hearts))));
+        assert_eq!("Td".parse::<Card>().unwrap().rank, Ranks::Ten);
+        assert_eq!("2c".parse::<Card>().unwrap().value, 2);
+
+        for bad in ["", "S", "1S", "11H", "AX", "KSS", "AS+", "AS+shiny", "AS+glass+steel"] {
+            assert!(bad.parse::<Card>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn parses_unicode_suits_and_modifiers() {
+        let card: Card = "q♥+Glass+holographic+redseal".parse().unwrap();
+        assert_eq!(card.suit, Suits::Hearts);
+        assert_eq!(card.rank, Ranks::Queen);
+        assert_eq!(card.enhancement, Some(Enhancements::Glass));
+        assert_eq!(card.edition, Some(Editions::Holographic));
+        assert_eq!(card.seal, Some(Seals::Red));
+
+        let gold: Card = "2c+goldseal+gold".parse().unwrap();
+        assert_eq!(gold.enhancement, Some(Enhancements::Gold));
+        assert_eq!(gold.seal, Some(Seals::Gold));
+    }
+
+    #[test]
+    fn notation_round_trips() {
+        for card in Deck::standard().cards {
+            assert_eq!(card.to_notation().parse(), Ok(card));
+        }
+
+        let mut card = Card::from_card_value(10, Some(Suits::Diamonds));
+        for enhancement in Enhancements::ALL {
+            for edition in Editions::ALL {
+                for seal in Seals::ALL {
+                    card.enhancement = Some(enhancement);
+                    card.edition = Some(edition);
+                    card.seal = Some(seal);
+                    assert_eq!(card.to_notation().parse(), Ok(card));
+                }
+            }
+        }
+        assert_eq!(card.to_notation(), "10D+lucky+poly+purpleseal");
+
+        assert_eq!(Card::blank().to_notation(), "?S");
+        assert_eq!("?S".parse(), Ok(Card::blank()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_as_notation() {
+        use crate::{hand::Hand, valid_hands::ValidHands};
+
+        let hand: Hand = "AS 10H+glass+redseal".parse().unwrap();
+        let json = serde_json::to_string(&hand).unwrap();
+        assert_eq!(json, r#"{"cards":["AS","10H+glass+redseal"],"size":2}"#);
+        assert_eq!(serde_json::from_str::<Hand>(&json).unwrap(), hand);
+
+        let deck = Deck::standard();
+        let json = serde_json::to_string(&deck).unwrap();
+        assert_eq!(serde_json::from_str::<Deck>(&json).unwrap().cards, deck.cards);
+
+        let pair = ValidHands::Pair(hand.cards[0], hand.cards[0]);
+        assert_eq!(serde_json::to_string(&pair).unwrap(), r#"{"Pair":["AS","AS"]}"#);
+
+        assert!(serde_json::from_str::<Card>(r#""1X""#).is_err());
     }
 }
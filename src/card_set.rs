@@ -0,0 +1,256 @@
+use crate::{
+    card::Card,
+    hand::Hand,
+    suit::{Ranks, Suits},
+    valid_hands::HandTypes,
+};
+
+const SUITS: [Suits; 4] = [Suits::Spades, Suits::Hearts, Suits::Clubs, Suits::Diamonds];
+const RANK_BITS: u16 = 0x1fff;
+const ACE_BIT: u16 = 1 << 12;
+
+/// A set of distinct cards packed into a u64: one 16 bit lane per suit (in Suits
+/// order) with bit 0 = Two up to bit 12 = Ace. The top three bits of each lane
+/// are unused. Cards without a real suit or rank (Card::blank) can't be stored,
+/// and neither can duplicates, so this is only a stand-in for Hand when every
+/// card is distinct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CardSet(pub u64);
+
+impl CardSet {
+    pub fn new() -> CardSet {
+        CardSet(0)
+    }
+
+    pub fn bit(card: &Card) -> Option<u64> {
+        if card.suit == Suits::Blank || card.rank == Ranks::Blank {
+            return None;
+        }
+        Some(1 << (card.suit as u64 * 16 + card.value as u64 - 2))
+    }
+
+    pub fn from_cards(cards: &[Card]) -> CardSet {
+        let mut set = CardSet::new();
+        for card in cards {
+            set.insert(card);
+        }
+        set
+    }
+
+    pub fn insert(&mut self, card: &Card) -> bool {
+        match CardSet::bit(card) {
+            Some(bit) => {
+                self.0 |= bit;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, card: &Card) {
+        if let Some(bit) = CardSet::bit(card) {
+            self.0 &= !bit;
+        }
+    }
+
+    pub fn contains(&self, card: &Card) -> bool {
+        CardSet::bit(card).is_some_and(|bit| self.0 & bit != 0)
+    }
+
+    pub fn contains_all(&self, other: CardSet) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn union(&self, other: CardSet) -> CardSet {
+        CardSet(self.0 | other.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn cards(&self) -> Vec<Card> {
+        let mut cards = vec![];
+        for suit in SUITS {
+            let mut mask = self.suit_mask(suit);
+            while mask != 0 {
+                let value = mask.trailing_zeros() as usize + 2;
+                cards.push(Card::from_card_value(value, Some(suit)));
+                mask &= mask - 1;
+            }
+        }
+        cards
+    }
+
+    pub fn suit_mask(&self, suit: Suits) -> u16 {
+        match suit {
+            Suits::Blank => 0,
+            _ => (self.0 >> (suit as u64 * 16)) as u16 & RANK_BITS,
+        }
+    }
+
+    fn lanes(&self) -> [u16; 4] {
+        SUITS.map(|suit| self.suit_mask(suit))
+    }
+
+    /// Ranks held in any suit.
+    pub fn rank_mask(&self) -> u16 {
+        let [s, h, c, d] = self.lanes();
+        s | h | c | d
+    }
+
+    // Ranks held in at least two, at least three and all four suits.
+    fn multiples(&self) -> (u16, u16, u16) {
+        let [s, h, c, d] = self.lanes();
+        let at_least_two = (s & h) | (s & c) | (s & d) | (h & c) | (h & d) | (c & d);
+        let at_least_three = (s & h & c) | (s & h & d) | (s & c & d) | (h & c & d);
+        let four = s & h & c & d;
+        (at_least_two, at_least_three, four)
+    }
+
+    /// The n-of-a-kind checks match ValidHands::has_n_of_a_kind, which looks for
+    /// a rank held exactly n times.
+    pub fn has_pair(&self) -> bool {
+        let (two, three, _) = self.multiples();
+        two & !three != 0
+    }
+
+    pub fn has_two_pair(&self) -> bool {
+        let (two, _, _) = self.multiples();
+        two.count_ones() >= 2
+    }
+
+    pub fn has_three_oak(&self) -> bool {
+        let (_, three, four) = self.multiples();
+        three & !four != 0
+    }
+
+    pub fn has_four_oak(&self) -> bool {
+        let (_, _, four) = self.multiples();
+        four != 0
+    }
+
+    pub fn has_full_house(&self) -> bool {
+        let (two, three, _) = self.multiples();
+        three != 0 && two & !three != 0
+    }
+
+    pub fn has_flush(&self) -> bool {
+        self.lanes().iter().any(|lane| lane.count_ones() >= 5)
+    }
+
+    pub fn has_straight(&self) -> bool {
+        is_straight(self.rank_mask())
+    }
+
+    pub fn has_straight_flush(&self) -> bool {
+        self.lanes().iter().any(|lane| is_straight(*lane))
+    }
+
+    pub fn contains_type(&self, hand_type: HandTypes) -> bool {
+        match hand_type {
+            HandTypes::HighCard => {
+                !self.is_empty() && HandTypes::ALL[1..].iter().all(|t| !self.contains_type(*t))
+            }
+            HandTypes::Pair => self.has_pair(),
+            HandTypes::TwoPair => self.has_two_pair(),
+            HandTypes::ThreeOAK => self.has_three_oak(),
+            HandTypes::Straight => self.has_straight(),
+            HandTypes::Flush => self.has_flush(),
+            HandTypes::FullHouse => self.has_full_house(),
+            HandTypes::FourOAK => self.has_four_oak(),
+            HandTypes::StraightFlush => self.has_straight_flush(),
+        }
+    }
+}
+
+/// Five consecutive ranks, with the Ace also allowed to sit below the Two.
+pub fn is_straight(ranks: u16) -> bool {
+    let ranks = ranks & RANK_BITS;
+    let m = (ranks << 1) | ((ranks & ACE_BIT) >> 12);
+    m & (m >> 1) & (m >> 2) & (m >> 3) & (m >> 4) != 0
+}
+
+impl From<&Hand> for CardSet {
+    fn from(hand: &Hand) -> Self {
+        CardSet::from_cards(&hand.cards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{seq::SliceRandom, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{card::Card, deck::Deck, hand::Hand, suit::Suits, valid_hands::HandTypes};
+
+    use super::CardSet;
+
+    #[test]
+    fn round_trips_cards() {
+        let deck = Deck::standard();
+        let set = CardSet::from_cards(&deck.cards);
+        assert_eq!(set.len(), 52);
+
+        let mut cards = set.cards();
+        let mut expected = deck.cards.clone();
+        cards.sort();
+        expected.sort();
+        assert_eq!(cards, expected);
+
+        assert!(!CardSet::new().insert(&Card::blank()));
+    }
+
+    #[test]
+    fn straights_include_ace_low_and_high() {
+        let wheel = CardSet::from_cards(
+            &[14, 2, 3, 4, 5].map(|v| Card::from_card_value(v, Some(Suits::Hearts))),
+        );
+        assert!(wheel.has_straight());
+        assert!(wheel.has_straight_flush());
+
+        let broadway = CardSet::from_cards(&[
+            Card::from_card_value(10, Some(Suits::Hearts)),
+            Card::from_card_value(11, Some(Suits::Spades)),
+            Card::from_card_value(12, Some(Suits::Hearts)),
+            Card::from_card_value(13, Some(Suits::Clubs)),
+            Card::from_card_value(14, Some(Suits::Hearts)),
+        ]);
+        assert!(broadway.has_straight());
+        assert!(!broadway.has_straight_flush());
+
+        let wrap = CardSet::from_cards(
+            &[12, 13, 14, 2, 3].map(|v| Card::from_card_value(v, Some(Suits::Hearts))),
+        );
+        assert!(!wrap.has_straight());
+    }
+
+    #[test]
+    fn matches_valid_hands_detectors() {
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let mut deck = Deck::standard();
+
+        for i in 0..3000 {
+            let hand_size = 5 + i % 4;
+            deck.cards.shuffle(&mut rng);
+            let hand = Hand {
+                cards: deck.cards[..hand_size].to_vec(),
+                size: hand_size,
+            };
+            let set = CardSet::from(&hand);
+
+            for hand_type in HandTypes::ALL {
+                assert_eq!(
+                    set.contains_type(hand_type),
+                    hand_type.detect(&hand),
+                    "{hand_type:?} disagrees on {:?}",
+                    hand.cards
+                );
+            }
+        }
+    }
+}
@@ -1,10 +1,20 @@
-use crate::{card::Card, hand::Hand, suit::Ranks};
+use rand::{seq::SliceRandom, Rng};
 
+use crate::{
+    card::Card,
+    hand::Hand,
+    suit::{Ranks, Suits},
+};
+
+/// A pile of cards. Cards are dealt from the back of `cards`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Deck {
     pub cards: Vec<Card>,
 }
 
 impl Deck {
+    /// The 52 card deck, ordered by suit then value.
     pub fn standard() -> Deck {
         let mut deck = Deck { cards: vec![] };
 
@@ -16,18 +26,18 @@ impl Deck {
                     match i {
                         11 => deck
                             .cards
-                            .push(Card::new(suit.clone(), Ranks::Jack, i)),
+                            .push(Card::new(suit, Ranks::Jack, i)),
                         12 => deck
                             .cards
-                            .push(Card::new(suit.clone(), Ranks::Queen, i)),
+                            .push(Card::new(suit, Ranks::Queen, i)),
                         13 => deck
                             .cards
-                            .push(Card::new(suit.clone(), Ranks::King, i)),
+                            .push(Card::new(suit, Ranks::King, i)),
                         14 => deck
                             .cards
-                            .push(Card::new(suit.clone(), Ranks::Ace, i)),
+                            .push(Card::new(suit, Ranks::Ace, i)),
 
-                        _ => deck.cards.push(Card::new(suit.clone(), Ranks::get_by_value(i), i)),
+                        _ => deck.cards.push(Card::new(suit, Ranks::get_by_value(i), i)),
                     };
                 }
             }
@@ -36,6 +46,30 @@ impl Deck {
         deck
     }
 
+    /// The Abandoned Deck: a standard deck without Jacks, Queens or Kings.
+    pub fn abandoned() -> Deck {
+        let mut deck = Deck::standard();
+        deck.cards.retain(|c| !matches!(c.rank, Ranks::Jack | Ranks::Queen | Ranks::King));
+        deck
+    }
+
+    /// The Checkered Deck: 26 Spades and 26 Hearts, each rank twice per suit.
+    pub fn checkered() -> Deck {
+        let mut deck = Deck::standard();
+        for card in deck.cards.iter_mut() {
+            card.suit = match card.suit {
+                Suits::Clubs => Suits::Spades,
+                Suits::Diamonds => Suits::Hearts,
+                suit => suit,
+            };
+        }
+        deck
+    }
+
+    pub fn shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.cards.shuffle(rng);
+    }
+
     pub fn deal_to_hand(&mut self, hand: &mut Hand) {
         while hand.cards.len() < hand.size {
             hand.cards.push(self.cards.pop().unwrap());
@@ -45,13 +79,13 @@ impl Deck {
     pub fn deal_n_cards(&mut self, n: usize) -> Vec<Card> {
         let mut draws: Vec<Card> = vec![];
         for _ in 0..n {
-            if self.cards.len() == 0 {
+            if self.cards.is_empty() {
                 return draws
             } else {
                 draws.push(self.cards.pop().unwrap());
             }
         }
 
-        return draws;
+        draws
     }
 }
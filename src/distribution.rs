@@ -0,0 +1,289 @@
+//! Whole distributions of simulated results rather than just their averages:
+//! chips scored per round, the best score on offer in an opening draw, or the
+//! ante a run reaches. A [`Distribution`] gives the mean, variance and
+//! percentiles, and a [`Histogram`] draws it as text or SVG.
+
+use std::fmt::Write;
+
+use crate::{
+    policy::best_play, round::RoundSetup, seed::Seed, simulation::run_indexed, stats::percentile,
+};
+
+/// Samples of one quantity, kept sorted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution {
+    samples: Vec<f64>,
+}
+
+impl Distribution {
+    /// NaNs are dropped.
+    pub fn new(mut samples: Vec<f64>) -> Distribution {
+        samples.retain(|x| !x.is_nan());
+        samples.sort_by(f64::total_cmp);
+        Distribution { samples }
+    }
+
+    pub fn samples(&self) -> &[f64] {
+        &self.samples
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.samples.iter().sum::<f64>() / self.len().max(1) as f64
+    }
+
+    /// The sample variance, 0 for fewer than two samples.
+    pub fn variance(&self) -> f64 {
+        if self.len() < 2 {
+            return 0.;
+        }
+        let mean = self.mean();
+        self.samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (self.len() - 1) as f64
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    pub fn min(&self) -> f64 {
+        self.samples.first().copied().unwrap_or(f64::NAN)
+    }
+
+    pub fn max(&self) -> f64 {
+        self.samples.last().copied().unwrap_or(f64::NAN)
+    }
+
+    /// The q quantile, 0 to 1, interpolating between samples.
+    pub fn percentile(&self, q: f64) -> f64 {
+        percentile(&self.samples, q)
+    }
+
+    /// Up to `bins` equal bins from the smallest sample to the largest. Whole
+    /// numbers spanning no more than `bins` values, like antes, get one bin
+    /// per value instead.
+    pub fn histogram(&self, bins: usize) -> Histogram {
+        let bins = bins.max(1);
+        let (min, max) = (self.min(), self.max());
+        if self.is_empty() {
+            return Histogram {
+                lower: 0.,
+                width: 1.,
+                counts: vec![],
+            };
+        }
+
+        let whole = self.samples.iter().all(|x| x.fract() == 0.);
+        let (lower, width, bins) = if whole && max - min < bins as f64 {
+            (min, 1., (max - min) as usize + 1)
+        } else if max == min {
+            (min, 1., 1)
+        } else {
+            (min, (max - min) / bins as f64, bins)
+        };
+
+        let mut counts = vec![0; bins];
+        for x in &self.samples {
+            // The largest sample closes the last bin.
+            let bin = (((x - lower) / width) as usize).min(bins - 1);
+            counts[bin] += 1;
+        }
+        Histogram {
+            lower,
+            width,
+            counts,
+        }
+    }
+}
+
+/// Counts of samples in equal bins, the first starting at `lower`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    pub lower: f64,
+    pub width: f64,
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// Where bin i starts.
+    pub fn edge(&self, i: usize) -> f64 {
+        self.lower + self.width * i as f64
+    }
+
+    // One bin per whole number, labelled with the number itself.
+    fn per_value(&self) -> bool {
+        self.width == 1. && self.lower.fract() == 0.
+    }
+
+    fn label(&self, i: usize) -> String {
+        if self.per_value() {
+            format!("{}", self.edge(i))
+        } else {
+            format!("{:.0}-{:.0}", self.edge(i), self.edge(i + 1))
+        }
+    }
+
+    /// One line per bin with a bar of `#` up to `width` long for the fullest
+    /// bin, then the count.
+    pub fn to_ascii(&self, width: usize) -> String {
+        let most = self.counts.iter().max().copied().unwrap_or(0).max(1);
+        let labels: Vec<String> = (0..self.counts.len()).map(|i| self.label(i)).collect();
+        let label_width = labels.iter().map(String::len).max().unwrap_or(0);
+
+        let mut text = String::new();
+        for (label, count) in labels.iter().zip(&self.counts) {
+            let bar = "#".repeat((count * width).div_ceil(most));
+            writeln!(text, "{label:>label_width$} | {bar} {count}").unwrap();
+        }
+        text
+    }
+
+    /// A standalone SVG bar chart, `width` by `height` pixels.
+    pub fn to_svg(&self, width: u32, height: u32, title: &str) -> String {
+        const MARGIN: f64 = 30.;
+        let (w, h) = (f64::from(width), f64::from(height));
+        let plot_width = w - 2. * MARGIN;
+        let plot_height = h - 2. * MARGIN;
+        let most = self.counts.iter().max().copied().unwrap_or(0).max(1) as f64;
+        let bar_width = plot_width / self.counts.len().max(1) as f64;
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="12">"#
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{}" y="20" text-anchor="middle">{}</text>"#,
+            w / 2.,
+            escape(title)
+        )
+        .unwrap();
+        for (i, count) in self.counts.iter().enumerate() {
+            let bar_height = plot_height * *count as f64 / most;
+            writeln!(
+                svg,
+                r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="#4c78a8"><title>{}: {count}</title></rect>"##,
+                MARGIN + bar_width * i as f64,
+                MARGIN + plot_height - bar_height,
+                (bar_width - 1.).max(1.),
+                bar_height,
+                self.label(i),
+            )
+            .unwrap();
+        }
+        if let Some(last) = self.counts.len().checked_sub(1) {
+            let (first_label, last_label) = match self.per_value() {
+                true => (self.label(0), self.label(last)),
+                false => (
+                    format!("{:.0}", self.lower),
+                    format!("{:.0}", self.edge(last + 1)),
+                ),
+            };
+            writeln!(
+                svg,
+                r#"<text x="{MARGIN}" y="{}">{first_label}</text>"#,
+                h - 10.
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"<text x="{}" y="{}" text-anchor="end">{last_label}</text>"#,
+                w - MARGIN,
+                h - 10.
+            )
+            .unwrap();
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// The highest score any play could make from each of `draws` opening hands of
+/// the setup, with its Jokers and hand levels. Draw i is the opening hand of
+/// round i of [`crate::round::play_rounds`] with the same seed.
+pub fn opening_best_scores(
+    setup: &RoundSetup,
+    draws: usize,
+    seed: &Seed,
+    threads: usize,
+) -> Vec<f64> {
+    run_indexed(0..draws, threads, seed.to_u64(), &|rng, _| {
+        best_play(&setup.deal(rng).view()).1.total()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{deck::Deck, round::RoundSetup, seed::Seed};
+
+    use super::{opening_best_scores, Distribution, Histogram};
+
+    #[test]
+    fn summarises_samples() {
+        let distribution = Distribution::new(vec![4., 1., f64::NAN, 3., 2.]);
+        assert_eq!(distribution.samples(), [1., 2., 3., 4.]);
+        assert_eq!(distribution.mean(), 2.5);
+        assert!((distribution.variance() - 5. / 3.).abs() < 1e-12);
+        assert_eq!((distribution.min(), distribution.max()), (1., 4.));
+        assert_eq!(distribution.percentile(0.5), 2.5);
+
+        let empty = Distribution::new(vec![]);
+        assert_eq!((empty.mean(), empty.variance()), (0., 0.));
+        assert!(empty.histogram(10).counts.is_empty());
+    }
+
+    #[test]
+    fn bins_samples() {
+        // Whole numbers over a small range get a bin each.
+        let antes = Distribution::new(vec![1., 1., 2., 3., 3., 3.]);
+        let histogram = antes.histogram(10);
+        assert_eq!(histogram.counts, [2, 1, 3]);
+        assert_eq!(
+            histogram.to_ascii(6),
+            "1 | #### 2\n2 | ## 1\n3 | ###### 3\n"
+        );
+
+        let chips = Distribution::new((0..100).map(f64::from).collect());
+        let histogram = chips.histogram(4);
+        assert_eq!(
+            histogram,
+            Histogram {
+                lower: 0.,
+                width: 99. / 4.,
+                counts: vec![25, 25, 25, 25]
+            }
+        );
+
+        let svg = histogram.to_svg(400, 200, "Chips <per round>");
+        assert_eq!(svg.matches("<rect").count(), 4);
+        assert!(svg.contains("Chips &lt;per round&gt;"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn best_scores_follow_the_deals() {
+        let setup = RoundSetup::new(Deck::standard(), 300.);
+        let seed = Seed::Numeric(6);
+        let scores = opening_best_scores(&setup, 200, &seed, 2);
+        assert_eq!(scores, opening_best_scores(&setup, 200, &seed, 3));
+        // Eight cards always hold at least an Ace-less High Card, and usually
+        // a Pair.
+        let distribution = Distribution::new(scores);
+        assert!(distribution.min() >= 7.);
+        assert!(distribution.percentile(0.5) >= 30., "{distribution:?}");
+    }
+}
@@ -0,0 +1,129 @@
+//! Hand types seen over a round rather than only in the opening draw. A
+//! [`Policy`] plays the round out, and every hand it holds along the way counts
+//! as a draw: the one dealt, then the one after each play or discard. A hand
+//! type is seen within K draws if any of the first K hands held it.
+
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    hand::Hand,
+    policy::Policy,
+    round::RoundSetup,
+    seed::Seed,
+    simulation::{run_indexed, Tally},
+    valid_hands::HandTypes,
+};
+
+/// The hand types held in any of the first `draws` hands of a round dealt
+/// from the setup and played by the policy, indexed as [`HandTypes::ALL`].
+/// Uses the rng like [`crate::round::play_round`], so the same rng sees the
+/// same round.
+pub fn seen_within(
+    setup: &RoundSetup,
+    policy: &mut dyn Policy,
+    draws: usize,
+    rng: &mut ChaCha8Rng,
+) -> [bool; HandTypes::ALL.len()] {
+    let mut seen = [false; HandTypes::ALL.len()];
+    let mut round = setup.deal(rng);
+    for draw in 0..draws {
+        let hand = Hand {
+            cards: round.hand().to_vec(),
+            size: setup.hand_size,
+        };
+        for hand_type in HandTypes::ALL {
+            seen[hand_type as usize] |= hand_type.detect(&hand);
+        }
+
+        if draw + 1 == draws || round.is_over() {
+            break;
+        }
+        let action = policy.choose(&round.view(), rng);
+        if round.apply(&action).is_err() {
+            break;
+        }
+    }
+    seen
+}
+
+/// How many of `rounds` rounds saw each hand type within `draws` draws, for
+/// every discard count from none to `max_discards`. Round i is dealt the same
+/// cards at every discard count, so only the discards differ between them.
+pub fn seen_by_discards<P: Policy + Clone + Sync>(
+    setup: &RoundSetup,
+    policy: &P,
+    draws: usize,
+    max_discards: u32,
+    rounds: usize,
+    seed: &Seed,
+    threads: usize,
+) -> Vec<Tally> {
+    (0..=max_discards)
+        .map(|discards| {
+            let setup = RoundSetup {
+                discards,
+                ..setup.clone()
+            };
+            let mut tally = Tally::default();
+            let seen = run_indexed(0..rounds, threads, seed.to_u64(), &|rng, _| {
+                seen_within(&setup, &mut policy.clone(), draws, rng)
+            });
+            for seen in seen {
+                tally.record_seen(seen);
+            }
+            tally
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        deck::Deck,
+        policy::{ChaseFlush, Greedy},
+        round::RoundSetup,
+        seed::Seed,
+        simulation::{simulate, SimConfig},
+        valid_hands::HandTypes,
+    };
+
+    use super::seen_by_discards;
+
+    #[test]
+    fn one_draw_is_the_opening_hand() {
+        let setup = RoundSetup::new(Deck::standard(), f64::INFINITY);
+        let seed = Seed::Numeric(8);
+        let tallies = seen_by_discards(&setup, &Greedy, 1, 0, 1500, &seed, 2);
+        assert_eq!(tallies.len(), 1);
+
+        let mut config = SimConfig::new(20_000, 8);
+        config.seed = seed;
+        let opening = simulate(&config);
+        for hand_type in [HandTypes::Pair, HandTypes::TwoPair, HandTypes::Flush] {
+            let (seen, drawn) = (
+                tallies[0].frequency(hand_type),
+                opening.frequency(hand_type),
+            );
+            assert!(
+                (seen - drawn).abs() < 0.04,
+                "{hand_type:?}: {seen} vs {drawn}"
+            );
+        }
+    }
+
+    #[test]
+    fn discards_help_the_chase() {
+        let setup = RoundSetup::new(Deck::standard(), f64::INFINITY);
+        let seed = Seed::Numeric(9);
+        let tallies = seen_by_discards(&setup, &ChaseFlush, 4, 3, 500, &seed, 2);
+        assert_eq!(tallies.len(), 4);
+        assert!(tallies.iter().all(|t| t.samples == 500));
+
+        let flushes: Vec<f64> = tallies
+            .iter()
+            .map(|t| t.frequency(HandTypes::Flush))
+            .collect();
+        assert!(flushes.is_sorted(), "{flushes:?}");
+        assert!(flushes[3] > flushes[0] + 0.1, "{flushes:?}");
+    }
+}
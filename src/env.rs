@@ -0,0 +1,294 @@
+//! A round against a blind as a reinforcement learning environment, in the
+//! style of Gym: [`Env::reset`] deals a round from a seed and [`Env::step`]
+//! takes a discrete action and returns the next observation, the reward and
+//! whether the round is over.
+//!
+//! Observations are a fixed length of [`OBSERVATION_LEN`] numbers:
+//!
+//! - [`HAND_SLOTS`] held cards of [`CARD_FEATURES`] each: whether the slot is
+//!   filled, then one-hot rank, suit, enhancement, edition and seal. Blank
+//!   cards have no rank or suit.
+//! - How many of each of the 52 cards are still in the draw pile, by suit
+//!   then rank from Ace down, then the count of blank cards.
+//! - Hands left, discards left, chips scored as a fraction of the blind and
+//!   the size of the draw pile.
+//! - The level of each hand type, High Card first.
+//! - How many of each Joker are held, in [`Jokers::ALL`] order.
+//!
+//! Actions are numbers below [`ACTIONS`]: the first half play and the second
+//! half discard one of the 1 to 5 card subsets of the slots, in the order
+//! [`picks`] lists them. [`Env::action_mask`] says which are legal. The
+//! reward is 1 for the step that beats the blind and 0 otherwise.
+
+use std::{error::Error, fmt};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    card::Card,
+    joker::Jokers,
+    modifiers::{Editions, Enhancements, Seals},
+    policy::picks,
+    round::{Action, ActionError, Round, RoundSetup},
+    score::MAX_PLAYED,
+    suit::{Ranks, Suits},
+    valid_hands::HandTypes,
+};
+
+/// Held cards the observation has room for.
+pub const HAND_SLOTS: usize = 8;
+pub const CARD_FEATURES: usize =
+    1 + 13 + 4 + Enhancements::ALL.len() + Editions::ALL.len() + Seals::ALL.len();
+pub const OBSERVATION_LEN: usize =
+    HAND_SLOTS * CARD_FEATURES + 52 + 1 + 4 + HandTypes::ALL.len() + Jokers::ALL.len();
+/// 218 plays of 1 to 5 of the 8 slots, then the same 218 as discards.
+pub const ACTIONS: usize = 2 * 218;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvError {
+    /// The setup's hand size is more than [`HAND_SLOTS`].
+    HandTooLarge(usize),
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvError::HandTooLarge(size) => write!(
+                f,
+                "hand size {size} is more than the {HAND_SLOTS} the observation holds"
+            ),
+        }
+    }
+}
+
+impl Error for EnvError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub observation: Vec<f32>,
+    pub reward: f32,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Env {
+    setup: RoundSetup,
+    round: Option<Round>,
+    // picks(HAND_SLOTS, MAX_PLAYED), indexed by action.
+    subsets: Vec<Vec<usize>>,
+}
+
+impl Env {
+    pub fn new(setup: RoundSetup) -> Result<Env, EnvError> {
+        if setup.hand_size > HAND_SLOTS {
+            return Err(EnvError::HandTooLarge(setup.hand_size));
+        }
+        Ok(Env {
+            setup,
+            round: None,
+            subsets: picks(HAND_SLOTS, MAX_PLAYED),
+        })
+    }
+
+    /// Deals a new round, the same one for the same seed.
+    pub fn reset(&mut self, seed: u64) -> Vec<f32> {
+        let round = self.setup.deal(&mut ChaCha8Rng::seed_from_u64(seed));
+        let observation = observe(&round);
+        self.round = Some(round);
+        observation
+    }
+
+    /// The round being played, None before the first reset.
+    pub fn round(&self) -> Option<&Round> {
+        self.round.as_ref()
+    }
+
+    pub fn decode(&self, action: usize) -> Option<Action> {
+        let subset = self.subsets.get(action % self.subsets.len())?.clone();
+        match action / self.subsets.len() {
+            0 => Some(Action::Play(subset)),
+            1 => Some(Action::Discard(subset)),
+            _ => None,
+        }
+    }
+
+    /// The number for an action, whatever order its positions are in.
+    pub fn encode(&self, action: &Action) -> Option<usize> {
+        let (picked, offset) = match action {
+            Action::Play(picked) => (picked, 0),
+            Action::Discard(picked) => (picked, self.subsets.len()),
+        };
+        let mut picked = picked.clone();
+        picked.sort_unstable();
+        let index = self.subsets.iter().position(|s| *s == picked)?;
+        Some(offset + index)
+    }
+
+    /// Which actions the current round accepts. All false before the first
+    /// reset and once the round is over.
+    pub fn action_mask(&self) -> Vec<bool> {
+        let Some(round) = self.round.as_ref().filter(|r| !r.is_over()) else {
+            return vec![false; ACTIONS];
+        };
+        let held = round.hand().len();
+        let can_discard = round.view().discards_left > 0;
+        (0..ACTIONS)
+            .map(|action| {
+                let in_hand = self.subsets[action % self.subsets.len()]
+                    .last()
+                    .is_some_and(|last| *last < held);
+                in_hand && (action < self.subsets.len() || can_discard)
+            })
+            .collect()
+    }
+
+    /// Takes an action. Illegal actions are rejected without changing the
+    /// round.
+    pub fn step(&mut self, action: usize) -> Result<Step, ActionError> {
+        let decoded = self.decode(action).ok_or(ActionError::BadCard)?;
+        let round = self.round.as_mut().ok_or(ActionError::RoundOver)?;
+        round.apply(&decoded)?;
+        Ok(Step {
+            observation: observe(round),
+            reward: if round.won() { 1. } else { 0. },
+            done: round.is_over(),
+        })
+    }
+}
+
+// The features of one held card, see the module docs.
+fn card_features(card: &Card, features: &mut [f32]) {
+    features[0] = 1.;
+    let mut offset = 1;
+    let mut one_hot = |index: Option<usize>, len: usize| {
+        if let Some(index) = index {
+            features[offset + index] = 1.;
+        }
+        offset += len;
+    };
+    one_hot(rank_index(card.rank), 13);
+    one_hot(suit_index(card.suit), 4);
+    one_hot(
+        card.enhancement.map(|e| e as usize),
+        Enhancements::ALL.len(),
+    );
+    one_hot(card.edition.map(|e| e as usize), Editions::ALL.len());
+    one_hot(card.seal.map(|s| s as usize), Seals::ALL.len());
+}
+
+// Ace first, as Ranks is declared.
+fn rank_index(rank: Ranks) -> Option<usize> {
+    (rank != Ranks::Blank).then_some(rank as usize)
+}
+
+fn suit_index(suit: Suits) -> Option<usize> {
+    (suit != Suits::Blank).then_some(suit as usize)
+}
+
+/// The observation for a round, laid out as the module docs describe.
+pub fn observe(round: &Round) -> Vec<f32> {
+    let view = round.view();
+    let mut observation = vec![0.; OBSERVATION_LEN];
+
+    for (slot, card) in view.hand.iter().take(HAND_SLOTS).enumerate() {
+        let start = slot * CARD_FEATURES;
+        card_features(card, &mut observation[start..start + CARD_FEATURES]);
+    }
+
+    let deck = HAND_SLOTS * CARD_FEATURES;
+    for card in &view.remaining {
+        let index = match (suit_index(card.suit), rank_index(card.rank)) {
+            (Some(suit), Some(rank)) => suit * 13 + rank,
+            _ => 52,
+        };
+        observation[deck + index] += 1.;
+    }
+
+    let state = deck + 53;
+    observation[state] = view.hands_left as f32;
+    observation[state + 1] = view.discards_left as f32;
+    observation[state + 2] = (view.chips / view.blind) as f32;
+    observation[state + 3] = view.remaining.len() as f32;
+
+    let levels = state + 4;
+    for (i, hand_type) in HandTypes::ALL.into_iter().enumerate() {
+        observation[levels + i] = view.levels.level(hand_type) as f32;
+    }
+
+    let jokers = levels + HandTypes::ALL.len();
+    for joker in view.jokers {
+        observation[jokers + joker.kind as usize] += 1.;
+    }
+    observation
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        deck::Deck,
+        joker::{Joker, Jokers},
+        round::{Action, ActionError, RoundSetup},
+    };
+
+    use super::{Env, EnvError, ACTIONS, CARD_FEATURES, HAND_SLOTS, OBSERVATION_LEN};
+
+    #[test]
+    fn encodes_actions_and_masks_illegal_ones() {
+        let mut setup = RoundSetup::new(Deck::standard(), 300.);
+        setup.hand_size = 6;
+        setup.discards = 0;
+        let mut env = Env::new(setup.clone()).unwrap();
+        assert_eq!(env.action_mask(), vec![false; ACTIONS]);
+
+        for action in [0, 217, 218, ACTIONS - 1] {
+            assert_eq!(env.encode(&env.decode(action).unwrap()), Some(action));
+        }
+        assert_eq!(env.decode(ACTIONS), None);
+        assert_eq!(env.encode(&Action::Discard(vec![2, 0])), Some(218 + 9));
+
+        env.reset(1);
+        let mask = env.action_mask();
+        for (action, allowed) in mask.into_iter().enumerate() {
+            let legal = match env.decode(action).unwrap() {
+                Action::Play(picked) => picked.iter().all(|i| *i < 6),
+                Action::Discard(_) => false,
+            };
+            assert_eq!(allowed, legal, "{action}");
+        }
+        assert_eq!(env.step(218), Err(ActionError::NoDiscardsLeft));
+        assert_eq!(env.step(7), Err(ActionError::BadCard));
+
+        setup.hand_size = 9;
+        assert_eq!(Env::new(setup).unwrap_err(), EnvError::HandTooLarge(9));
+    }
+
+    #[test]
+    fn plays_a_round_to_the_end() {
+        let mut setup = RoundSetup::new(Deck::standard(), 1e9);
+        setup.jokers = vec![Joker::new(Jokers::Joker); 2];
+        let mut env = Env::new(setup).unwrap();
+
+        let observation = env.reset(7);
+        assert_eq!(observation.len(), OBSERVATION_LEN);
+        assert_eq!(observation, env.reset(7));
+        // Eight filled slots, one of each card held and 44 left to draw.
+        let filled = (0..HAND_SLOTS).filter(|s| observation[s * CARD_FEATURES] == 1.);
+        assert_eq!(filled.count(), 8);
+        let deck = &observation[HAND_SLOTS * CARD_FEATURES..][..53];
+        assert_eq!(deck.iter().sum::<f32>(), 44.);
+        assert_eq!(observation[OBSERVATION_LEN - 30], 2.);
+
+        let mut steps = 0;
+        loop {
+            let step = env.step(0).unwrap();
+            steps += 1;
+            assert_eq!(step.reward, 0.);
+            if step.done {
+                break;
+            }
+        }
+        assert_eq!(steps, 4);
+        assert_eq!(env.step(0), Err(ActionError::RoundOver));
+    }
+}
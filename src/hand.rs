@@ -1,25 +1,52 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
 use crate::{
-    card::Card,
-    deck::Deck,
+    card::{Card, ParseCardError},
     suit::Suits,
-    valid_hands::{self, ValidHands},
+    valid_hands::ValidHands,
 };
 
+/// Cards held by the player, filled by `Deck::deal_to_hand` up to `size`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Hand {
     pub cards: Vec<Card>,
     pub size: usize,
 }
 
-impl Hand {
-    pub fn default() -> Hand {
+impl Default for Hand {
+    fn default() -> Hand {
         Hand {
             cards: vec![],
             size: 5,
         }
     }
+}
+
+// Cards in notation separated by spaces or commas, e.g. "AS KH 10D+glass 2C".
+// The hand's size is the number of cards given.
+impl FromStr for Hand {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cards = s
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<Card>, _>>()?;
+        let size = cards.len();
+        Ok(Hand { cards, size })
+    }
+}
+
+impl Hand {
+    pub fn to_notation(&self) -> String {
+        self.cards
+            .iter()
+            .map(Card::to_notation)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
 
     pub fn of_size(hand_size: usize) -> Hand {
         Hand {
@@ -29,7 +56,7 @@ impl Hand {
     }
 
     pub fn add_to_hand(&mut self, mut cards: Vec<Card>) {
-        while cards.len() > 0 {
+        while !cards.is_empty() {
             self.cards.push(cards.remove(0));
         }
     }
@@ -49,7 +76,7 @@ impl Hand {
     pub fn held_suits(&self) -> HashMap<Suits, Vec<usize>> {
         let mut suit_map: HashMap<Suits, Vec<usize>> = HashMap::new();
         for c in &self.cards {
-            suit_map.entry(c.suit).or_insert(Vec::new()).push(c.value);
+            suit_map.entry(c.suit).or_default().push(c.value);
         }
         suit_map
     }
@@ -59,9 +86,9 @@ impl Hand {
         for c in &self.cards {
             suit_map
                 .entry(c.suit)
-                .or_insert(Hand::default())
+                .or_default()
                 .cards
-                .push(c.clone());
+                .push(*c);
         }
         suit_map
     }
@@ -103,19 +130,21 @@ impl Hand {
         let mut run_length = 0;
         for card in self.cards.iter().skip(1) {
             run_length += 1;
-            if card.value > largest_card_value {
+            // compare in the same ordering, or a second low Ace looks like a new value.
+            let card_value = ace_hi(card.value);
+            if card_value > largest_card_value {
                 result.push((run_length, largest_card_value));
                 run_length = 0;
-                largest_card_value = ace_hi(card.value);
+                largest_card_value = card_value;
             }
         }
-        if run_length == 0 {
-            // the case where the final run is only the final card.
-            result.push((1, largest_card_value))
-        }
+        // the final run is never followed by a larger card, so push it here,
+        // including any duplicates of the final card value.
+        result.push((run_length + 1, largest_card_value));
         result
     }
 
+    /// Every hand the detectors find in these cards.
     pub fn contains(&self) -> Vec<ValidHands> {
         let mut valid_hands: Vec<ValidHands> = vec![];
 
@@ -160,7 +189,7 @@ impl Hand {
             }
         }
 
-        return valid_hands;
+        valid_hands
     }
 
     pub fn sort_by_rank_ace_high(&mut self) {
@@ -214,4 +243,16 @@ mod tests {
         let suit_map = hand.held_suits();
         assert_eq!(suit_map, h)
     }
+
+    #[test]
+    fn parses_and_formats_notation() {
+        let hand: Hand = "AS KH, 10D+glass  2c".parse().unwrap();
+        assert_eq!(hand.size, 4);
+        assert_eq!(hand.cards[0], Card::from_card_value(14, Some(Suits::Spades)));
+        assert_eq!(hand.to_notation(), "AS KH 10D+glass 2C");
+        assert_eq!(hand.to_notation().parse(), Ok(hand));
+
+        assert!("AS XX".parse::<Hand>().is_err());
+        assert_eq!("".parse::<Hand>().map(|h| h.size), Ok(0));
+    }
 }
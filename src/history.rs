@@ -0,0 +1,491 @@
+//! Hand histories: everything dealt, played and discarded in one simulated
+//! round or run, as a text log that can be replayed.
+//!
+//! A game is identified by its seed and index, the same way
+//! [`crate::round::play_rounds`] and [`crate::run::play_runs`] number them, so
+//! any game from a comparison can be recorded again on its own. A log looks
+//! like this, one event per line:
+//!
+//! ```text
+//! round seed=42 game=3 blind=300
+//! deal 2H 7H 9H KH 4C 4S JD QS
+//! discard 4 5 6 7 (4C 4S JD QS)
+//! draw 3H 5D 8C 10S
+//! play 0 1 2 3 4 (2H 7H 9H KH 3H) Flush 68 x 4
+//! end won 272
+//! ```
+//!
+//! [`replay`] deals the game again from the seed, takes the logged actions in
+//! order and checks that every event comes out the same.
+
+use std::{error::Error, fmt, str::FromStr};
+
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    card::Card,
+    policy::Policy,
+    round::{play_round_logged, Action, RoundResult, RoundSetup, View},
+    run::{play_run_logged, RunResult},
+    seed::Seed,
+    simulation::chunk_rng,
+    valid_hands::HandTypes,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// A run moved on to the next blind.
+    Blind {
+        ante: u32,
+        chips: f64,
+    },
+    /// The opening hand.
+    Deal(Vec<Card>),
+    /// Cards drawn after a play or discard, in the order they were drawn.
+    Draw(Vec<Card>),
+    Play {
+        picked: Vec<usize>,
+        cards: Vec<Card>,
+        hand_type: HandTypes,
+        chips: f64,
+        mult: f64,
+    },
+    Discard {
+        picked: Vec<usize>,
+        cards: Vec<Card>,
+    },
+    /// An action the round refused, which ends it.
+    Rejected(Action),
+    End {
+        won: bool,
+        chips: f64,
+    },
+}
+
+fn positions(picked: &[usize]) -> String {
+    picked
+        .iter()
+        .map(usize::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn notation(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(Card::to_notation)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Blind { ante, chips } => write!(f, "blind ante={ante} chips={chips}"),
+            Event::Deal(cards) => write!(f, "deal {}", notation(cards)),
+            Event::Draw(cards) => write!(f, "draw {}", notation(cards)),
+            Event::Play {
+                picked,
+                cards,
+                hand_type,
+                chips,
+                mult,
+            } => write!(
+                f,
+                "play {} ({}) {} {chips} x {mult}",
+                positions(picked),
+                notation(cards),
+                hand_type.to_str()
+            ),
+            Event::Discard { picked, cards } => {
+                write!(f, "discard {} ({})", positions(picked), notation(cards))
+            }
+            Event::Rejected(Action::Play(picked)) => {
+                write!(f, "rejected play {}", positions(picked))
+            }
+            Event::Rejected(Action::Discard(picked)) => {
+                write!(f, "rejected discard {}", positions(picked))
+            }
+            Event::End { won, chips } => {
+                write!(f, "end {} {chips}", if *won { "won" } else { "lost" })
+            }
+        }
+    }
+}
+
+fn parse_number<T: FromStr>(text: &str) -> Result<T, String> {
+    text.parse()
+        .map_err(|_| format!("expected a number, found {text:?}"))
+}
+
+fn parse_positions(text: &str) -> Result<Vec<usize>, String> {
+    text.split_whitespace().map(parse_number).collect()
+}
+
+fn parse_cards(text: &str) -> Result<Vec<Card>, String> {
+    text.split_whitespace()
+        .map(|card| card.parse::<Card>().map_err(|e| e.to_string()))
+        .collect()
+}
+
+// "0 1 (AS KS)" and what follows the closing bracket.
+fn parse_picked(text: &str) -> Result<(Vec<usize>, Vec<Card>, &str), String> {
+    let (picked, rest) = text.split_once('(').ok_or("expected the picked cards")?;
+    let (cards, rest) = rest.split_once(')').ok_or("expected ')'")?;
+    Ok((parse_positions(picked)?, parse_cards(cards)?, rest.trim()))
+}
+
+// The value of `key=` in a line of key=value pairs.
+fn field<'a>(fields: &'a str, key: &str) -> Result<&'a str, String> {
+    fields
+        .split_whitespace()
+        .find_map(|pair| pair.strip_prefix(key)?.strip_prefix('='))
+        .ok_or_else(|| format!("expected {key}="))
+}
+
+impl FromStr for Event {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (kind, rest) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+        match kind {
+            "blind" => Ok(Event::Blind {
+                ante: parse_number(field(rest, "ante")?)?,
+                chips: parse_number(field(rest, "chips")?)?,
+            }),
+            "deal" => Ok(Event::Deal(parse_cards(rest)?)),
+            "draw" => Ok(Event::Draw(parse_cards(rest)?)),
+            "play" => {
+                let (picked, cards, score) = parse_picked(rest)?;
+                // The hand type's name has spaces in it, so read from the end.
+                let mut words = score.rsplitn(4, ' ');
+                let mult = parse_number(words.next().unwrap_or_default())?;
+                if words.next() != Some("x") {
+                    return Err("expected chips x mult".to_string());
+                }
+                let chips = parse_number(words.next().unwrap_or_default())?;
+                let hand_type = words
+                    .next()
+                    .unwrap_or_default()
+                    .parse::<HandTypes>()
+                    .map_err(|e| e.to_string())?;
+                Ok(Event::Play {
+                    picked,
+                    cards,
+                    hand_type,
+                    chips,
+                    mult,
+                })
+            }
+            "discard" => {
+                let (picked, cards, _) = parse_picked(rest)?;
+                Ok(Event::Discard { picked, cards })
+            }
+            "rejected" => match rest.split_once(' ').unwrap_or((rest, "")) {
+                ("play", picked) => Ok(Event::Rejected(Action::Play(parse_positions(picked)?))),
+                ("discard", picked) => {
+                    Ok(Event::Rejected(Action::Discard(parse_positions(picked)?)))
+                }
+                _ => Err("expected a rejected play or discard".to_string()),
+            },
+            "end" => match rest.split_once(' ') {
+                Some(("won", chips)) => Ok(Event::End {
+                    won: true,
+                    chips: parse_number(chips)?,
+                }),
+                Some(("lost", chips)) => Ok(Event::End {
+                    won: false,
+                    chips: parse_number(chips)?,
+                }),
+                _ => Err("expected won or lost and the chips".to_string()),
+            },
+            _ => Err(format!("unknown event {kind:?}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Games {
+    /// A single round against the setup's blind.
+    Round,
+    /// A whole run, see [`crate::run`].
+    Run,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct History {
+    pub game: Games,
+    pub seed: Seed,
+    /// Which of the games played from the seed this is, counting from 0.
+    pub index: usize,
+    /// The blind of a single round. Runs set their own.
+    pub blind: f64,
+    pub events: Vec<Event>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseHistoryError {
+    /// Counting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseHistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseHistoryError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayError {
+    /// The first event that came out differently, counting from 0.
+    pub event: usize,
+    pub logged: Option<Box<Event>>,
+    pub replayed: Option<Box<Event>>,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |event: &Option<Box<Event>>| match event {
+            Some(event) => event.to_string(),
+            None => "nothing".to_string(),
+        };
+        write!(
+            f,
+            "replay differs at event {}: logged {}, replayed {}",
+            self.event,
+            show(&self.logged),
+            show(&self.replayed)
+        )
+    }
+}
+
+impl Error for ReplayError {}
+
+impl History {
+    /// Plays game `index` of the seed as a single round and logs it.
+    pub fn record_round(
+        setup: &RoundSetup,
+        policy: &mut dyn Policy,
+        seed: &Seed,
+        index: usize,
+    ) -> (RoundResult, History) {
+        let mut events = vec![];
+        let mut rng = chunk_rng(seed.to_u64(), index);
+        let result = play_round_logged(setup, policy, &mut rng, &mut |e| events.push(e));
+        let history = History {
+            game: Games::Round,
+            seed: seed.clone(),
+            index,
+            blind: setup.blind,
+            events,
+        };
+        (result, history)
+    }
+
+    /// Plays game `index` of the seed as a whole run and logs it.
+    pub fn record_run(
+        setup: &RoundSetup,
+        policy: &mut dyn Policy,
+        seed: &Seed,
+        index: usize,
+    ) -> (RunResult, History) {
+        let mut events = vec![];
+        let mut rng = chunk_rng(seed.to_u64(), index);
+        let result = play_run_logged(setup, policy, &mut rng, &mut |e| events.push(e));
+        let history = History {
+            game: Games::Run,
+            seed: seed.clone(),
+            index,
+            blind: setup.blind,
+            events,
+        };
+        (result, history)
+    }
+
+    /// The plays and discards taken, in order, including any that were
+    /// rejected.
+    pub fn actions(&self) -> Vec<Action> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Play { picked, .. } => Some(Action::Play(picked.clone())),
+                Event::Discard { picked, .. } => Some(Action::Discard(picked.clone())),
+                Event::Rejected(action) => Some(action.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn to_text(&self) -> String {
+        let game = match self.game {
+            Games::Round => "round",
+            Games::Run => "run",
+        };
+        let mut text = format!(
+            "{game} seed={} game={} blind={}\n",
+            self.seed, self.index, self.blind
+        );
+        for event in &self.events {
+            text += &format!("{event}\n");
+        }
+        text
+    }
+
+    /// Reads a log written by [`History::to_text`]. Blank lines are skipped.
+    pub fn parse(text: &str) -> Result<History, ParseHistoryError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let error = |line: usize, message: String| ParseHistoryError {
+            line: line + 1,
+            message,
+        };
+
+        let (number, header) = lines
+            .next()
+            .ok_or_else(|| error(0, "empty history".to_string()))?;
+        let (game, fields) = header.trim().split_once(' ').unwrap_or((header, ""));
+        let game = match game {
+            "round" => Games::Round,
+            "run" => Games::Run,
+            _ => return Err(error(number, "expected round or run".to_string())),
+        };
+        let header = || -> Result<History, String> {
+            Ok(History {
+                game,
+                seed: field(fields, "seed")?.parse().map_err(|e| format!("{e}"))?,
+                index: parse_number(field(fields, "game")?)?,
+                blind: parse_number(field(fields, "blind")?)?,
+                events: vec![],
+            })
+        };
+        let mut history = header().map_err(|e| error(number, e))?;
+
+        for (number, line) in lines {
+            history
+                .events
+                .push(line.parse().map_err(|e| error(number, e))?);
+        }
+        Ok(history)
+    }
+}
+
+// Takes the logged actions in order. Running out gives an empty play, which
+// the round rejects.
+struct Scripted(std::vec::IntoIter<Action>);
+
+impl Policy for Scripted {
+    fn name(&self) -> &'static str {
+        "replay"
+    }
+
+    fn choose(&mut self, _: &View, _: &mut ChaCha8Rng) -> Action {
+        self.0.next().unwrap_or(Action::Play(vec![]))
+    }
+}
+
+/// Plays the logged game again from its seed with the logged actions and
+/// checks every event matches. `setup` must be the one the game was recorded
+/// with; the blind of a single round is taken from the log.
+pub fn replay(setup: &RoundSetup, history: &History) -> Result<(), ReplayError> {
+    let mut setup = setup.clone();
+    setup.blind = history.blind;
+    let mut policy = Scripted(history.actions().into_iter());
+    let replayed = match history.game {
+        Games::Round => History::record_round(&setup, &mut policy, &history.seed, history.index).1,
+        Games::Run => History::record_run(&setup, &mut policy, &history.seed, history.index).1,
+    };
+
+    let events = history.events.len().max(replayed.events.len());
+    match (0..events).find(|i| history.events.get(*i) != replayed.events.get(*i)) {
+        Some(event) => Err(ReplayError {
+            event,
+            logged: history.events.get(event).cloned().map(Box::new),
+            replayed: replayed.events.get(event).cloned().map(Box::new),
+        }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        deck::Deck,
+        mcts::Mcts,
+        policy::{ChaseFlush, Random},
+        round::{play_rounds, RoundSetup},
+        seed::Seed,
+    };
+
+    use super::{replay, Event, History};
+
+    #[test]
+    fn logs_read_back_and_replay() {
+        let setup = RoundSetup::new(Deck::standard(), 300.);
+        let seed = Seed::Numeric(9);
+        let (result, history) = History::record_round(&setup, &mut ChaseFlush, &seed, 4);
+        // The same game as the fifth of a comparison.
+        assert_eq!(play_rounds(&setup, &ChaseFlush, 5, &seed, 2)[4], result);
+
+        let text = history.to_text();
+        assert!(
+            text.starts_with("round seed=9 game=4 blind=300\ndeal "),
+            "{text}"
+        );
+        assert_eq!(History::parse(&text).unwrap(), history);
+        assert_eq!(
+            history.events.last(),
+            Some(&Event::End {
+                won: result.won,
+                chips: result.chips
+            })
+        );
+        assert_eq!(replay(&setup, &history), Ok(()));
+
+        // Policies that use randomness replay too, as whole runs.
+        let (_, run) = History::record_run(&setup, &mut Random, &seed, 0);
+        assert!(matches!(run.events[0], Event::Blind { ante: 1, .. }));
+        assert_eq!(
+            replay(&setup, &History::parse(&run.to_text()).unwrap()),
+            Ok(())
+        );
+        let (_, run) = History::record_run(&setup, &mut Mcts::new(20), &seed, 1);
+        assert_eq!(replay(&setup, &run), Ok(()));
+    }
+
+    #[test]
+    fn replay_finds_the_first_difference() {
+        let setup = RoundSetup::new(Deck::standard(), 300.);
+        let (_, mut history) = History::record_round(&setup, &mut ChaseFlush, &Seed::Numeric(2), 0);
+        let Event::Deal(cards) = &mut history.events[0] else {
+            panic!("a round starts with the deal");
+        };
+        cards.swap(0, 1);
+        assert_eq!(replay(&setup, &history).unwrap_err().event, 0);
+
+        let mut text = history.to_text();
+        text.insert_str(text.find('\n').unwrap() + 1, "shuffle\n");
+        assert_eq!(History::parse(&text).unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn events_read_back() {
+        for line in [
+            "blind ante=2 chips=1200",
+            "deal 2H 7H 9H KH+glass+redseal 4C",
+            "discard 4 5 (4C 4S)",
+            "play 0 1 2 (AS AH AD) Three OAK 63 x 3.5",
+            "rejected play 0 9",
+            "end lost 180",
+        ] {
+            assert_eq!(line.parse::<Event>().unwrap().to_string(), line);
+        }
+        for bad in ["", "deal 1Z", "play 0 (AS) High Card 5", "end tied 0"] {
+            assert!(bad.parse::<Event>().is_err(), "{bad}");
+        }
+    }
+}
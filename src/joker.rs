@@ -0,0 +1,342 @@
+//! The common scoring Jokers: the ones whose effect depends only on the played
+//! hand, so they can be applied by [`crate::score`] without any run state.
+//!
+//! Jokers are written by their in-game name with an optional edition suffix,
+//! e.g. "Jolly Joker" or "the-duo+poly".
+
+use std::{collections::HashMap, error::Error, fmt, str::FromStr};
+
+use crate::{
+    card::Card,
+    hand::Hand,
+    modifiers::{Editions, Enhancements},
+    suit::{Ranks, Suits},
+    valid_hands::HandTypes,
+};
+
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub enum Jokers {
+    Joker,
+    GreedyJoker,
+    LustyJoker,
+    WrathfulJoker,
+    GluttonousJoker,
+    JollyJoker,
+    ZanyJoker,
+    MadJoker,
+    CrazyJoker,
+    DrollJoker,
+    SlyJoker,
+    WilyJoker,
+    CleverJoker,
+    DeviousJoker,
+    CraftyJoker,
+    HalfJoker,
+    AbstractJoker,
+    ScaryFace,
+    SmileyFace,
+    EvenSteven,
+    OddTodd,
+    Scholar,
+    Fibonacci,
+    GrosMichel,
+    Cavendish,
+    TheDuo,
+    TheTrio,
+    TheFamily,
+    TheOrder,
+    TheTribe,
+}
+
+impl Jokers {
+    pub const ALL: [Jokers; 30] = [
+        Jokers::Joker,
+        Jokers::GreedyJoker,
+        Jokers::LustyJoker,
+        Jokers::WrathfulJoker,
+        Jokers::GluttonousJoker,
+        Jokers::JollyJoker,
+        Jokers::ZanyJoker,
+        Jokers::MadJoker,
+        Jokers::CrazyJoker,
+        Jokers::DrollJoker,
+        Jokers::SlyJoker,
+        Jokers::WilyJoker,
+        Jokers::CleverJoker,
+        Jokers::DeviousJoker,
+        Jokers::CraftyJoker,
+        Jokers::HalfJoker,
+        Jokers::AbstractJoker,
+        Jokers::ScaryFace,
+        Jokers::SmileyFace,
+        Jokers::EvenSteven,
+        Jokers::OddTodd,
+        Jokers::Scholar,
+        Jokers::Fibonacci,
+        Jokers::GrosMichel,
+        Jokers::Cavendish,
+        Jokers::TheDuo,
+        Jokers::TheTrio,
+        Jokers::TheFamily,
+        Jokers::TheOrder,
+        Jokers::TheTribe,
+    ];
+
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Jokers::Joker => "Joker",
+            Jokers::GreedyJoker => "Greedy Joker",
+            Jokers::LustyJoker => "Lusty Joker",
+            Jokers::WrathfulJoker => "Wrathful Joker",
+            Jokers::GluttonousJoker => "Gluttonous Joker",
+            Jokers::JollyJoker => "Jolly Joker",
+            Jokers::ZanyJoker => "Zany Joker",
+            Jokers::MadJoker => "Mad Joker",
+            Jokers::CrazyJoker => "Crazy Joker",
+            Jokers::DrollJoker => "Droll Joker",
+            Jokers::SlyJoker => "Sly Joker",
+            Jokers::WilyJoker => "Wily Joker",
+            Jokers::CleverJoker => "Clever Joker",
+            Jokers::DeviousJoker => "Devious Joker",
+            Jokers::CraftyJoker => "Crafty Joker",
+            Jokers::HalfJoker => "Half Joker",
+            Jokers::AbstractJoker => "Abstract Joker",
+            Jokers::ScaryFace => "Scary Face",
+            Jokers::SmileyFace => "Smiley Face",
+            Jokers::EvenSteven => "Even Steven",
+            Jokers::OddTodd => "Odd Todd",
+            Jokers::Scholar => "Scholar",
+            Jokers::Fibonacci => "Fibonacci",
+            Jokers::GrosMichel => "Gros Michel",
+            Jokers::Cavendish => "Cavendish",
+            Jokers::TheDuo => "The Duo",
+            Jokers::TheTrio => "The Trio",
+            Jokers::TheFamily => "The Family",
+            Jokers::TheOrder => "The Order",
+            Jokers::TheTribe => "The Tribe",
+        }
+    }
+}
+
+/// A Joker held in one of the Joker slots, with its edition if it has one.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Joker {
+    pub kind: Jokers,
+    pub edition: Option<Editions>,
+}
+
+impl Joker {
+    pub fn new(kind: Jokers) -> Joker {
+        Joker {
+            kind,
+            edition: None,
+        }
+    }
+
+    pub fn to_notation(&self) -> String {
+        match self.edition {
+            Some(edition) => format!("{}+{}", self.kind.to_str(), edition.to_str()),
+            None => self.kind.to_str().to_string(),
+        }
+    }
+
+    /// Applies the Joker's effect for one scoring card as it is triggered.
+    /// Stone and blank cards have no rank or suit, and Wild cards count as every
+    /// suit.
+    pub fn on_scored(&self, card: &Card, chips: &mut f64, mult: &mut f64) {
+        if card.enhancement == Some(Enhancements::Stone) || card.rank == Ranks::Blank {
+            return;
+        }
+        let suited =
+            |suit: Suits| card.suit == suit || card.enhancement == Some(Enhancements::Wild);
+        let face = matches!(card.rank, Ranks::King | Ranks::Queen | Ranks::Jack);
+        match self.kind {
+            Jokers::GreedyJoker if suited(Suits::Diamonds) => *mult += 3.,
+            Jokers::LustyJoker if suited(Suits::Hearts) => *mult += 3.,
+            Jokers::WrathfulJoker if suited(Suits::Spades) => *mult += 3.,
+            Jokers::GluttonousJoker if suited(Suits::Clubs) => *mult += 3.,
+            Jokers::ScaryFace if face => *chips += 30.,
+            Jokers::SmileyFace if face => *mult += 5.,
+            Jokers::EvenSteven if !face && card.value <= 10 && card.value.is_multiple_of(2) => {
+                *mult += 4.
+            }
+            Jokers::OddTodd
+                if card.value == 14 || (card.value <= 10 && !card.value.is_multiple_of(2)) =>
+            {
+                *chips += 31.
+            }
+            Jokers::Scholar if card.rank == Ranks::Ace => {
+                *chips += 20.;
+                *mult += 4.;
+            }
+            Jokers::Fibonacci if matches!(card.value, 14 | 2 | 3 | 5 | 8) => *mult += 8.,
+            _ => {}
+        }
+    }
+
+    /// Applies the Joker's effect once the played cards have all scored,
+    /// including its edition. `jokers` is how many Jokers are held.
+    pub fn independent(&self, played: &[Card], jokers: usize, chips: &mut f64, mult: &mut f64) {
+        match self.edition {
+            Some(Editions::Foil) => *chips += 50.,
+            Some(Editions::Holographic) => *mult += 10.,
+            _ => {}
+        }
+        let has = |hand_type| contains(played, hand_type);
+        match self.kind {
+            Jokers::Joker => *mult += 4.,
+            Jokers::JollyJoker if has(HandTypes::Pair) => *mult += 8.,
+            Jokers::ZanyJoker if has(HandTypes::ThreeOAK) => *mult += 12.,
+            Jokers::MadJoker if has(HandTypes::TwoPair) => *mult += 10.,
+            Jokers::CrazyJoker if has(HandTypes::Straight) => *mult += 12.,
+            Jokers::DrollJoker if has(HandTypes::Flush) => *mult += 10.,
+            Jokers::SlyJoker if has(HandTypes::Pair) => *chips += 50.,
+            Jokers::WilyJoker if has(HandTypes::ThreeOAK) => *chips += 100.,
+            Jokers::CleverJoker if has(HandTypes::TwoPair) => *chips += 80.,
+            Jokers::DeviousJoker if has(HandTypes::Straight) => *chips += 100.,
+            Jokers::CraftyJoker if has(HandTypes::Flush) => *chips += 80.,
+            Jokers::HalfJoker if played.len() <= 3 => *mult += 20.,
+            Jokers::AbstractJoker => *mult += 3. * jokers as f64,
+            Jokers::GrosMichel => *mult += 15.,
+            Jokers::Cavendish => *mult *= 3.,
+            Jokers::TheDuo if has(HandTypes::Pair) => *mult *= 2.,
+            Jokers::TheTrio if has(HandTypes::ThreeOAK) => *mult *= 3.,
+            Jokers::TheFamily if has(HandTypes::FourOAK) => *mult *= 4.,
+            Jokers::TheOrder if has(HandTypes::Straight) => *mult *= 3.,
+            Jokers::TheTribe if has(HandTypes::Flush) => *mult *= 2.,
+            _ => {}
+        }
+        if self.edition == Some(Editions::Polychrome) {
+            *mult *= 1.5;
+        }
+    }
+}
+
+// Whether the played cards contain the hand type the way Balatro's Jokers read
+// it: a rank held at least n times counts, so a Full House contains a Pair and
+// a Four OAK contains a Three OAK. Stone cards don't take part.
+fn contains(played: &[Card], hand_type: HandTypes) -> bool {
+    let cards: Vec<Card> = played
+        .iter()
+        .filter(|c| c.enhancement != Some(Enhancements::Stone))
+        .copied()
+        .collect();
+    let mut held: HashMap<usize, usize> = HashMap::new();
+    for card in &cards {
+        *held.entry(card.value).or_default() += 1;
+    }
+    let ranks_with = |n: usize| held.values().filter(|count| **count >= n).count();
+
+    match hand_type {
+        HandTypes::HighCard => !cards.is_empty(),
+        HandTypes::Pair => ranks_with(2) >= 1,
+        HandTypes::TwoPair => ranks_with(2) >= 2,
+        HandTypes::ThreeOAK => ranks_with(3) >= 1,
+        HandTypes::FourOAK => ranks_with(4) >= 1,
+        HandTypes::FullHouse => ranks_with(3) >= 1 && ranks_with(2) >= 2,
+        HandTypes::Straight | HandTypes::Flush | HandTypes::StraightFlush => {
+            let size = cards.len();
+            hand_type.detect(&Hand { cards, size })
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseJokerError(String);
+
+impl fmt::Display for ParseJokerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown joker {:?}: expected a joker name with an optional +foil, +holo or +poly",
+            self.0
+        )
+    }
+}
+
+impl Error for ParseJokerError {}
+
+// Names match to_str in any case, with spaces, dashes or underscores between
+// words, like HandTypes.
+impl FromStr for Joker {
+    type Err = ParseJokerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let err = || ParseJokerError(s.to_string());
+        let (name, edition) = match s.split_once('+') {
+            Some((name, edition)) => (name, Some(Editions::from_name(edition).ok_or_else(err)?)),
+            None => (s, None),
+        };
+        let squash = |name: &str| -> String {
+            name.chars()
+                .filter(|c| !matches!(c, ' ' | '-' | '_'))
+                .collect::<String>()
+                .to_ascii_lowercase()
+        };
+        let kind = Jokers::ALL
+            .into_iter()
+            .find(|j| squash(j.to_str()) == squash(name))
+            .ok_or_else(err)?;
+        Ok(Joker { kind, edition })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Joker {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_notation())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Joker {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let notation = String::deserialize(deserializer)?;
+        notation.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{card::Card, modifiers::Editions, valid_hands::HandTypes};
+
+    use super::{contains, Joker, Jokers};
+
+    fn cards(notation: &str) -> Vec<Card> {
+        notation.split(' ').map(|c| c.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn parses_names_and_editions() {
+        assert_eq!("Jolly Joker".parse(), Ok(Joker::new(Jokers::JollyJoker)));
+        assert_eq!(
+            "the-duo+Polychrome".parse(),
+            Ok(Joker {
+                kind: Jokers::TheDuo,
+                edition: Some(Editions::Polychrome)
+            })
+        );
+        for joker in Jokers::ALL {
+            let joker = Joker {
+                kind: joker,
+                edition: Some(Editions::Foil),
+            };
+            assert_eq!(joker.to_notation().parse(), Ok(joker));
+        }
+        for bad in ["", "Jolly", "Joker+", "Joker+glass"] {
+            assert!(bad.parse::<Joker>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn contains_counts_bigger_hands() {
+        let full_house = cards("KS KH KD 2C 2D");
+        for hand_type in [HandTypes::Pair, HandTypes::TwoPair, HandTypes::ThreeOAK] {
+            assert!(contains(&full_house, hand_type), "{hand_type:?}");
+        }
+        assert!(!contains(&full_house, HandTypes::FourOAK));
+        assert!(contains(&cards("7S 7H 7D 7C"), HandTypes::Pair));
+        assert!(!contains(&cards("7S 7H+stone"), HandTypes::Pair));
+    }
+}
@@ -0,0 +1,87 @@
+//! Balatro hand odds: cards, decks and hand detectors, plus a seeded Monte Carlo
+//! simulator for how often each hand type shows up in an opening draw.
+//!
+//! The common types are re-exported at the crate root:
+//!
+//! ```
+//! use monte::{best_hand, simulate, Card, CardSet, HandTypes, Seed, SimConfig, Suits};
+//!
+//! let cards: Vec<Card> = [2, 3, 4, 5, 6]
+//!     .map(|value| Card::from_card_value(value, Some(Suits::Hearts)))
+//!     .to_vec();
+//! assert_eq!(best_hand(CardSet::from_cards(&cards)), HandTypes::StraightFlush);
+//!
+//! let mut config = SimConfig::new(10_000, 8);
+//! config.seed = Seed::Numeric(42);
+//! let tally = simulate(&config);
+//! assert!(tally.frequency(HandTypes::Pair) > 0.3);
+//! ```
+//!
+//! The detectors in [`valid_hands`] work on a [`Hand`] and report the cards
+//! that made each hand. [`CardSet`] and [`best_hand`] are the fast paths used
+//! by the simulator and agree with them on hands of distinct cards. [`odds`]
+//! gives exact probabilities for any deck, [`draws`] the chance of holding each
+//! hand type at some point in a round and [`sweep`] tabulates both over hand
+//! sizes, decks and discards, while [`thinning`] ranks single changes to the
+//! deck. [`score`] scores a played hand with any [`joker`]s and hand levels and
+//! [`solve`] ranks discards. [`round`] plays whole rounds against a blind with
+//! a [`policy`] making the decisions, [`mcts`] searches for the decision most
+//! likely to win and [`env`](mod@env) wraps a round for training agents.
+//! [`run`] chains rounds into a run of rising blinds and [`tournament`]
+//! compares policies on the same deals, with [`history`] logging and replaying
+//! any one game and [`distribution`] describing the spread of their results.
+//! [`save`] reads the run in progress from Balatro's own save file.
+//!
+//! The `serde` feature adds Serialize and Deserialize to the card, hand, deck,
+//! joker and seed types, and writes a [`report::Report`] as JSON. Cards and
+//! seeds are written as their text form ("10H+glass", "7LB2WVPK") so saved
+//! data doesn't depend on the structs' layout.
+//! The `scenario` feature, on by default, reads starting positions from TOML or
+//! JSON files with `scenario::Scenario` and turns on `serde`. The `tui` feature
+//! adds `tui`, for playing rounds by hand in the terminal.
+
+pub mod balatro_rng;
+pub mod card;
+pub mod card_set;
+pub mod deck;
+pub mod distribution;
+pub mod draws;
+pub mod env;
+pub mod hand;
+pub mod history;
+pub mod joker;
+pub mod lookup;
+pub mod lua;
+pub mod mcts;
+pub mod modifiers;
+pub mod odds;
+pub mod policy;
+pub mod report;
+pub mod round;
+pub mod run;
+pub mod save;
+#[cfg(feature = "scenario")]
+pub mod scenario;
+pub mod score;
+pub mod seed;
+pub mod simulation;
+pub mod solve;
+pub mod stats;
+pub mod suit;
+pub mod sweep;
+pub mod thinning;
+pub mod tournament;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod valid_hands;
+pub mod variance;
+
+pub use card::Card;
+pub use card_set::CardSet;
+pub use deck::Deck;
+pub use hand::Hand;
+pub use lookup::best_hand;
+pub use seed::Seed;
+pub use simulation::{simulate, SimConfig, Tally};
+pub use suit::{Ranks, Suits};
+pub use valid_hands::{HandTypes, ValidHands};
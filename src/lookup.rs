@@ -0,0 +1,217 @@
+//! Table driven best-hand evaluator.
+//!
+//! The best category of a set of distinct cards only depends on three things,
+//! each of which is looked up in a small table built on first use:
+//! - each suit's 13 bit rank mask -> straight flush / flush / nothing
+//! - the combined rank mask -> straight or not
+//! - how many ranks are held exactly twice, exactly three times and four times
+//!   -> best n-of-a-kind category
+//!
+//! The last table is filled in by running the ValidHands detectors on one
+//! representative hand per profile, so it agrees with them by construction.
+//! Profiles too big for a representative, which need far more cards than a
+//! hand holds, go to the detectors directly.
+
+use std::sync::OnceLock;
+
+use crate::{
+    card::Card,
+    card_set::{is_straight, CardSet},
+    hand::Hand,
+    suit::Suits,
+    valid_hands::HandTypes,
+};
+
+const SUITS: [Suits; 4] = [Suits::Spades, Suits::Hearts, Suits::Clubs, Suits::Diamonds];
+const MAX_PAIRS: usize = 6;
+const MAX_TRIPS: usize = 4;
+const MAX_QUADS: usize = 3;
+
+struct Tables {
+    suit: Vec<HandTypes>,
+    straight: Vec<bool>,
+    // None where no representative hand could be built.
+    multiples: [[[Option<HandTypes>; MAX_QUADS + 1]; MAX_TRIPS + 1]; MAX_PAIRS + 1],
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(build_tables)
+}
+
+fn build_tables() -> Tables {
+    let suit = (0..1u16 << 13)
+        .map(|mask| {
+            if is_straight(mask) {
+                HandTypes::StraightFlush
+            } else if mask.count_ones() >= 5 {
+                HandTypes::Flush
+            } else {
+                HandTypes::HighCard
+            }
+        })
+        .collect();
+
+    let straight = (0..1u16 << 13).map(is_straight).collect();
+
+    let mut multiples = [[[None; MAX_QUADS + 1]; MAX_TRIPS + 1]; MAX_PAIRS + 1];
+    for (pairs, by_trips) in multiples.iter_mut().enumerate() {
+        for (trips, by_quads) in by_trips.iter_mut().enumerate() {
+            for (quads, best) in by_quads.iter_mut().enumerate() {
+                *best = representative_hand(pairs, trips, quads).map(|hand| HandTypes::best(&hand));
+            }
+        }
+    }
+
+    Tables {
+        suit,
+        straight,
+        multiples,
+    }
+}
+
+// A hand with the given multiples and nothing else: ranks avoid five in a row
+// and suits rotate so no suit reaches five cards.
+fn representative_hand(pairs: usize, trips: usize, quads: usize) -> Option<Hand> {
+    const VALUES: [usize; 10] = [2, 3, 4, 5, 7, 8, 9, 10, 12, 13];
+    let counts: Vec<usize> = [(quads, 4), (trips, 3), (pairs, 2)]
+        .iter()
+        .flat_map(|(n, count)| std::iter::repeat_n(*count, *n))
+        .collect();
+    if counts.len() > VALUES.len() {
+        return None;
+    }
+
+    let mut cards = vec![];
+    let mut next_suit = 0;
+    for (value, count) in VALUES.iter().zip(counts) {
+        for _ in 0..count {
+            cards.push(Card::from_card_value(*value, Some(SUITS[next_suit % 4])));
+            next_suit += 1;
+        }
+    }
+    if SUITS
+        .iter()
+        .any(|suit| cards.iter().filter(|c| c.suit == *suit).count() >= 5)
+    {
+        // Big profiles can't avoid a flush, and can't occur in 8 cards anyway.
+        return None;
+    }
+
+    let size = cards.len();
+    Some(Hand { cards, size })
+}
+
+/// Best category in the set, e.g. Flush for a hand holding both a flush and a
+/// pair. Agrees with HandTypes::best on the same cards, for any number of them;
+/// sets with more multiples than the tables cover (well past a hand's worth of
+/// cards) are handed to HandTypes::best and lose the constant time lookup.
+pub fn best_hand(cards: CardSet) -> HandTypes {
+    let tables = tables();
+    let lanes = SUITS.map(|suit| cards.suit_mask(suit));
+
+    let mut best = HandTypes::HighCard;
+    for lane in lanes {
+        best = best.max(tables.suit[lane as usize]);
+    }
+    if best == HandTypes::StraightFlush {
+        return best;
+    }
+
+    let [s, h, c, d] = lanes;
+    if tables.straight[(s | h | c | d) as usize] {
+        best = best.max(HandTypes::Straight);
+    }
+
+    let two = (s & h) | (s & c) | (s & d) | (h & c) | (h & d) | (c & d);
+    let three = (s & h & c) | (s & h & d) | (s & c & d) | (h & c & d);
+    let four = s & h & c & d;
+    let pairs = (two & !three).count_ones() as usize;
+    let trips = (three & !four).count_ones() as usize;
+    let quads = four.count_ones() as usize;
+
+    let multiples = tables
+        .multiples
+        .get(pairs)
+        .and_then(|by_trips| by_trips.get(trips))
+        .and_then(|by_quads| by_quads.get(quads))
+        .copied()
+        .flatten();
+    match multiples {
+        Some(multiples) => best.max(multiples),
+        None => {
+            let cards = cards.cards();
+            let size = cards.len();
+            HandTypes::best(&Hand { cards, size })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{seq::SliceRandom, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{card::Card, card_set::CardSet, deck::Deck, hand::Hand, valid_hands::HandTypes};
+
+    use super::best_hand;
+
+    #[test]
+    fn matches_valid_hands_detectors() {
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        let mut deck = Deck::standard();
+        let mut seen = vec![];
+
+        for i in 0..20_000 {
+            let hand_size = 5 + i % 4;
+            deck.cards.shuffle(&mut rng);
+            let hand = Hand {
+                cards: deck.cards[..hand_size].to_vec(),
+                size: hand_size,
+            };
+
+            let expected = HandTypes::best(&hand);
+            assert_eq!(
+                best_hand(CardSet::from(&hand)),
+                expected,
+                "disagrees on {:?}",
+                hand.cards
+            );
+            if !seen.contains(&expected) {
+                seen.push(expected);
+            }
+        }
+
+        // Make sure the sample actually covered the rare categories.
+        assert_eq!(seen.len(), HandTypes::ALL.len());
+    }
+
+    #[test]
+    fn large_sets_match_the_detectors() {
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+        let mut deck = Deck::standard();
+        for i in 0..400 {
+            let hand_size = 9 + i % 30;
+            deck.cards.shuffle(&mut rng);
+            let hand = Hand {
+                cards: deck.cards[..hand_size].to_vec(),
+                size: hand_size,
+            };
+            assert_eq!(
+                best_hand(CardSet::from(&hand)),
+                HandTypes::best(&hand),
+                "disagrees on {:?}",
+                hand.cards
+            );
+        }
+
+        // Four pairs and four trips with no straight, spread so every suit
+        // holds five: no representative hand has that profile, and the Full
+        // House beats the Flushes.
+        let cards: Vec<Card> = "2S 2H 3C 3D 4S 4H 7C 7D 8S 8H 8C 9D 9S 9H JC JD JS QH QC QD"
+            .split(' ')
+            .map(|c| c.parse().unwrap())
+            .collect();
+        assert_eq!(best_hand(CardSet::from_cards(&cards)), HandTypes::FullHouse);
+    }
+}
@@ -0,0 +1,381 @@
+//! A reader for Lua table literals, the format Balatro writes its save files
+//! in: `return {["GAME"]={["dollars"]=4,...},...}`. Only constructors and
+//! literals are supported, not expressions.
+
+use std::{error::Error, fmt};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LuaValue {
+    Nil,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Table(LuaTable),
+}
+
+impl LuaValue {
+    pub fn as_table(&self) -> Option<&LuaTable> {
+        match self {
+            LuaValue::Table(table) => Some(table),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            LuaValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            LuaValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            LuaValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+}
+
+/// The entries of a table in the order they were written. Positional entries
+/// get the integer keys 1, 2, 3... like in Lua.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LuaTable {
+    pub entries: Vec<(LuaValue, LuaValue)>,
+}
+
+impl LuaTable {
+    /// The value under a string key. Later entries win, as in Lua.
+    pub fn get(&self, key: &str) -> Option<&LuaValue> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(k, _)| k.as_str() == Some(key))
+            .map(|(_, v)| v)
+    }
+
+    pub fn table(&self, key: &str) -> Option<&LuaTable> {
+        self.get(key).and_then(LuaValue::as_table)
+    }
+
+    pub fn str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(LuaValue::as_str)
+    }
+
+    pub fn number(&self, key: &str) -> Option<f64> {
+        self.get(key).and_then(LuaValue::as_number)
+    }
+
+    /// The values under integer keys, ordered by key. Saves write arrays with
+    /// explicit keys in any order, e.g. `{[2]=...,[1]=...}`.
+    pub fn array(&self) -> Vec<&LuaValue> {
+        let mut items: Vec<(f64, &LuaValue)> = self
+            .entries
+            .iter()
+            .filter_map(|(k, v)| match k {
+                LuaValue::Number(n) if n.fract() == 0. => Some((*n, v)),
+                _ => None,
+            })
+            .collect();
+        items.sort_by(|a, b| a.0.total_cmp(&b.0));
+        items.into_iter().map(|(_, v)| v).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLuaError {
+    /// Byte offset into the source where parsing failed.
+    pub offset: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseLuaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.offset)
+    }
+}
+
+impl Error for ParseLuaError {}
+
+/// Parses a single value, optionally preceded by `return` as in a save file.
+pub fn parse(source: &str) -> Result<LuaValue, ParseLuaError> {
+    let mut parser = Parser {
+        src: source.as_bytes(),
+        pos: 0,
+    };
+    parser.skip_space();
+    if parser.src[parser.pos..].starts_with(b"return")
+        && !parser
+            .src
+            .get(parser.pos + 6)
+            .is_some_and(|b| b.is_ascii_alphanumeric() || *b == b'_')
+    {
+        parser.pos += 6;
+    }
+    let value = parser.value()?;
+    parser.skip_space();
+    if parser.pos < parser.src.len() {
+        return Err(parser.error("unexpected text after the value"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    src: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> ParseLuaError {
+        ParseLuaError {
+            offset: self.pos,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), ParseLuaError> {
+        self.skip_space();
+        if self.peek() != Some(byte) {
+            return Err(self.error(&format!("expected '{}'", byte as char)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    // Whitespace and `--` line comments.
+    fn skip_space(&mut self) {
+        loop {
+            while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+                self.pos += 1;
+            }
+            if !self.src[self.pos..].starts_with(b"--") {
+                return;
+            }
+            while self.peek().is_some_and(|b| b != b'\n') {
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<LuaValue, ParseLuaError> {
+        self.skip_space();
+        match self.peek() {
+            Some(b'{') => self.table().map(LuaValue::Table),
+            Some(b'"' | b'\'') => self.string().map(LuaValue::String),
+            Some(b'-' | b'.' | b'0'..=b'9') => self.number().map(LuaValue::Number),
+            Some(b) if b.is_ascii_alphabetic() || b == b'_' => match self.name().as_str() {
+                "true" => Ok(LuaValue::Bool(true)),
+                "false" => Ok(LuaValue::Bool(false)),
+                "nil" => Ok(LuaValue::Nil),
+                // tostring() writes these for infinite and NaN numbers.
+                "inf" => Ok(LuaValue::Number(f64::INFINITY)),
+                "nan" => Ok(LuaValue::Number(f64::NAN)),
+                _ => Err(self.error("expected a value")),
+            },
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    fn name(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|b| b.is_ascii_alphanumeric() || b == b'_')
+        {
+            self.pos += 1;
+        }
+        String::from_utf8_lossy(&self.src[start..self.pos]).into_owned()
+    }
+
+    fn table(&mut self) -> Result<LuaTable, ParseLuaError> {
+        self.expect(b'{')?;
+        let mut table = LuaTable::default();
+        let mut next_index = 1.;
+        loop {
+            self.skip_space();
+            match self.peek() {
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(table);
+                }
+                None => return Err(self.error("unclosed table")),
+                _ => {}
+            }
+
+            let start = self.pos;
+            let key = match self.peek() {
+                Some(b'[') => {
+                    self.pos += 1;
+                    let key = self.value()?;
+                    self.expect(b']')?;
+                    self.expect(b'=')?;
+                    Some(key)
+                }
+                Some(b) if b.is_ascii_alphabetic() || b == b'_' => {
+                    let name = self.name();
+                    self.skip_space();
+                    if self.peek() == Some(b'=') {
+                        self.pos += 1;
+                        Some(LuaValue::String(name))
+                    } else {
+                        // A positional true, false or nil; read it again.
+                        self.pos = start;
+                        None
+                    }
+                }
+                _ => None,
+            };
+            let key = key.unwrap_or_else(|| {
+                next_index += 1.;
+                LuaValue::Number(next_index - 1.)
+            });
+            let value = self.value()?;
+            table.entries.push((key, value));
+
+            self.skip_space();
+            match self.peek() {
+                Some(b',' | b';') => self.pos += 1,
+                Some(b'}') => {}
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    // Quoted strings with the escapes string.format("%q") produces, which
+    // include a backslash before a literal newline and decimal `\ddd` bytes.
+    fn string(&mut self) -> Result<String, ParseLuaError> {
+        let quote = self.src[self.pos];
+        self.pos += 1;
+        let mut bytes = vec![];
+        loop {
+            let b = self.peek().ok_or_else(|| self.error("unclosed string"))?;
+            self.pos += 1;
+            match b {
+                b if b == quote => break,
+                b'\\' => {
+                    let escaped = self.peek().ok_or_else(|| self.error("unclosed string"))?;
+                    self.pos += 1;
+                    match escaped {
+                        b'n' | b'\n' => bytes.push(b'\n'),
+                        b'r' => bytes.push(b'\r'),
+                        b't' => bytes.push(b'\t'),
+                        b'a' => bytes.push(7),
+                        b'b' => bytes.push(8),
+                        b'f' => bytes.push(12),
+                        b'v' => bytes.push(11),
+                        b'0'..=b'9' => {
+                            let start = self.pos - 1;
+                            while self.pos - start < 3
+                                && self.peek().is_some_and(|b| b.is_ascii_digit())
+                            {
+                                self.pos += 1;
+                            }
+                            let digits = std::str::from_utf8(&self.src[start..self.pos]).unwrap();
+                            let byte = digits
+                                .parse::<u8>()
+                                .map_err(|_| self.error("escape is larger than a byte"))?;
+                            bytes.push(byte);
+                        }
+                        other => bytes.push(other),
+                    }
+                }
+                b => bytes.push(b),
+            }
+        }
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    fn number(&mut self) -> Result<f64, ParseLuaError> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+            self.skip_space();
+            if self.peek().is_some_and(|b| b.is_ascii_alphabetic()) {
+                return match self.name().as_str() {
+                    "inf" => Ok(f64::NEG_INFINITY),
+                    "nan" => Ok(f64::NAN),
+                    _ => Err(self.error("expected a number")),
+                };
+            }
+        }
+        let digits_start = self.pos;
+        while self.peek().is_some_and(|b| {
+            b.is_ascii_alphanumeric()
+                || b == b'.'
+                || (matches!(b, b'+' | b'-') && matches!(self.src[self.pos - 1], b'e' | b'E'))
+        }) {
+            self.pos += 1;
+        }
+        let text = std::str::from_utf8(&self.src[digits_start..self.pos]).unwrap();
+        let magnitude = match text.strip_prefix("0x").or(text.strip_prefix("0X")) {
+            Some(hex) => i64::from_str_radix(hex, 16).ok().map(|n| n as f64),
+            None => text.parse::<f64>().ok(),
+        };
+        let magnitude = magnitude.ok_or(ParseLuaError {
+            offset: start,
+            message: "expected a number".to_string(),
+        })?;
+        Ok(if self.src[start] == b'-' {
+            -magnitude
+        } else {
+            magnitude
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, LuaTable, LuaValue};
+
+    #[test]
+    fn reads_save_style_tables() {
+        let value = parse(
+            "return {[\"GAME\"]={[\"dollars\"]=-3,[\"won\"]=false,[\"chips\"]=1.5e+3,},\
+             [\"cards\"]={[2]=\"b\",[1]=\"a\\\"q\\\"\\\n\\065\",},}",
+        )
+        .unwrap();
+        let root = value.as_table().unwrap();
+        let game = root.table("GAME").unwrap();
+        assert_eq!(game.number("dollars"), Some(-3.));
+        assert_eq!(game.get("won"), Some(&LuaValue::Bool(false)));
+        assert_eq!(game.number("chips"), Some(1500.));
+
+        let cards: Vec<_> = root.table("cards").unwrap().array();
+        let cards: Vec<_> = cards.iter().map(|c| c.as_str().unwrap()).collect();
+        assert_eq!(cards, ["a\"q\"\nA", "b"]);
+    }
+
+    #[test]
+    fn reads_plain_constructors() {
+        let value = parse("{ x = 1; 'one', true, { nil }, -- comment\n 0x10 }").unwrap();
+        let table = value.as_table().unwrap();
+        assert_eq!(table.number("x"), Some(1.));
+        assert_eq!(
+            table.array(),
+            [
+                &LuaValue::String("one".to_string()),
+                &LuaValue::Bool(true),
+                &LuaValue::Table(LuaTable {
+                    entries: vec![(LuaValue::Number(1.), LuaValue::Nil)]
+                }),
+                &LuaValue::Number(16.),
+            ]
+        );
+
+        for bad in [
+            "", "{", "{[1]}", "{1 2}", "\"open", "return", "{} {}", "{x = y}",
+        ] {
+            assert!(parse(bad).is_err(), "{bad}");
+        }
+    }
+}
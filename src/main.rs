@@ -1,116 +1,1142 @@
-mod card;
-mod deck;
-mod hand;
-mod valid_hands;
-mod suit;
+use std::{fs, path::PathBuf, process};
 
-use deck::Deck;
-use hand::Hand;
-use rand::seq::SliceRandom;
-use valid_hands::ValidHands;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use monte::{
+    balatro_rng::BalatroRng,
+    distribution::{opening_best_scores, Distribution},
+    draws::seen_by_discards,
+    history::{self, History},
+    mcts::Mcts,
+    odds,
+    policy::{ChaseFlush, ChaseStraight, Greedy, Policy, Random},
+    report::Report,
+    round::{play_rounds, Action, RoundSetup, Summary, View},
+    run::{play_runs, RunResult},
+    scenario::Scenario,
+    score::{self, HandLevels, MAX_PLAYED},
+    simulate,
+    simulation::default_threads,
+    solve::{self, Objectives},
+    stats::IntervalMethods,
+    sweep::{Point, Removed, Sweep},
+    thinning,
+    tournament::{compare_all, Entry, Standing},
+    variance, Card, Deck, HandTypes, Seed, SimConfig, Tally,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+#[derive(Parser)]
+#[command(version, about = "Hand odds, scoring and discard advice for Balatro")]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Estimate how often each hand type is drawn by sampling shuffled decks
+    Simulate(SimulateArgs),
+    /// Exact probabilities of drawing each hand type
+    Odds {
+        /// 8 unless set by the scenario
+        #[arg(long)]
+        hand_size: Option<usize>,
+        #[arg(long, value_enum, default_value_t = DeckTypes::Standard)]
+        deck: DeckTypes,
+        /// Use the whole deck from a scenario file or save.jkr instead of --deck
+        #[arg(long, conflicts_with = "deck")]
+        scenario: Option<PathBuf>,
+    },
+    /// Score cards played as one hand, e.g. `evaluate AS KS QS JS 10S+glass`
+    Evaluate {
+        #[arg(required = true, num_args = 1..=MAX_PLAYED)]
+        cards: Vec<Card>,
+        /// Score with the Jokers and hand levels from a scenario file or save.jkr
+        #[arg(long)]
+        scenario: Option<PathBuf>,
+    },
+    /// Rank which of the held cards to discard, e.g. `solve 3H 5H JH KH 2C 7D 9S QC`
+    Solve(SolveArgs),
+    /// Play whole rounds against a blind with automated policies, dealing each
+    /// policy the same cards
+    Play(PlayArgs),
+    /// Play policies against each other on the same seeds, rounds and whole
+    /// runs, and test the differences between them
+    Tournament(TournamentArgs),
+    /// Log everything that happens in one game of a policy, round or run
+    Record(RecordArgs),
+    /// Play a logged game again and check it comes out the same
+    Replay {
+        log: PathBuf,
+        #[arg(long, value_enum, default_value_t = DeckTypes::Standard)]
+        deck: DeckTypes,
+        /// The scenario file or save.jkr the game was recorded from
+        #[arg(long, conflicts_with = "deck")]
+        scenario: Option<PathBuf>,
+    },
+    /// Play rounds by hand in the terminal
+    #[cfg(feature = "tui")]
+    Tui {
+        /// Chips needed to win; 300 unless set by the scenario
+        #[arg(long)]
+        blind: Option<f64>,
+        #[arg(long)]
+        seed: Option<Seed>,
+        #[arg(long, value_enum, default_value_t = DeckTypes::Standard)]
+        deck: DeckTypes,
+        /// Start from a scenario file or save.jkr instead of --deck
+        #[arg(long, conflicts_with = "deck")]
+        scenario: Option<PathBuf>,
+    },
+    /// Search for the play or discard most likely to beat the blind from a
+    /// scenario file or save.jkr
+    Advise(AdviseArgs),
+    /// The opening hand of every blind and the boss of each ante on a Balatro
+    /// seed, shuffled the way the game does
+    Deal {
+        /// A Balatro seed such as 7LB2WVPK, even one of only digits
+        #[arg(value_parser = Seed::balatro)]
+        seed: Seed,
+        #[arg(long, default_value_t = 1)]
+        antes: u32,
+        #[arg(long, value_enum, default_value_t = DeckTypes::Standard)]
+        deck: DeckTypes,
+        /// Deal from a scenario file or save.jkr instead of --deck
+        #[arg(long, conflicts_with = "deck")]
+        scenario: Option<PathBuf>,
+    },
+    /// The spread of a policy's results: percentiles, mean, variance and a
+    /// histogram
+    Distribution(DistributionArgs),
+    /// How often each hand type is held at some point in the first draws of a
+    /// round, by how many discards the policy may use
+    Draws(DrawsArgs),
+    /// Tabulate hand type odds over several hand sizes, decks with ranks
+    /// removed and discard counts at once
+    Sweep(SweepArgs),
+    /// Rank the cards, ranks and suits to take out of the deck, or cards to
+    /// copy, by how much they raise the odds of a hand type
+    Thinning {
+        /// The hand type to rank changes by
+        #[arg(long, default_value = "Flush")]
+        target: HandTypes,
+        /// 8 unless set by the scenario
+        #[arg(long)]
+        hand_size: Option<usize>,
+        #[arg(long, value_enum, default_value_t = DeckTypes::Standard)]
+        deck: DeckTypes,
+        /// Start from the whole deck in a scenario file or save.jkr instead of --deck
+        #[arg(long, conflicts_with = "deck")]
+        scenario: Option<PathBuf>,
+        /// How many changes to show, best first; 0 shows them all
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+}
+
+#[derive(Args)]
+struct SimulateArgs {
+    #[arg(long, default_value_t = 100_000)]
+    samples: usize,
+    /// 8 unless set by the scenario
+    #[arg(long)]
+    hand_size: Option<usize>,
+    /// A number or a Balatro seed such as 7LB2WVPK; random when left out
+    #[arg(long)]
+    seed: Option<Seed>,
+    #[arg(long, default_value_t = default_threads())]
+    threads: usize,
+    #[arg(long, value_enum, default_value_t = DeckTypes::Standard)]
+    deck: DeckTypes,
+    /// Draw from the whole deck in a scenario file or save.jkr instead of --deck
+    #[arg(long, conflicts_with = "deck")]
+    scenario: Option<PathBuf>,
+    #[arg(long, default_value_t = 0.95)]
+    confidence: f64,
+    #[arg(long, value_enum, default_value_t = Intervals::Wilson)]
+    interval: Intervals,
+    /// Stop early once every interval is narrower than this
+    #[arg(long)]
+    target_width: Option<f64>,
+    #[arg(long, value_enum, default_value_t = Estimators::Plain)]
+    estimator: Estimators,
+    #[arg(long, value_enum, default_value_t = Formats::Text)]
+    format: Formats,
+}
+
+#[derive(Args)]
+struct SolveArgs {
+    /// The held cards, taken from the scenario when left out
+    #[arg(required_unless_present = "scenario")]
+    cards: Vec<Card>,
+    /// Maximise the chance of this hand type or better instead of the score
+    #[arg(long)]
+    target: Option<HandTypes>,
+    /// Redraws per discard option
+    #[arg(long, default_value_t = 2000)]
+    samples: usize,
+    #[arg(long)]
+    seed: Option<Seed>,
+    #[arg(long, default_value_t = default_threads())]
+    threads: usize,
+    #[arg(long, value_enum, default_value_t = DeckTypes::Standard)]
+    deck: DeckTypes,
+    /// Redraw from the deck in a scenario file or save.jkr instead of --deck
+    #[arg(long, conflicts_with = "deck")]
+    scenario: Option<PathBuf>,
+    /// How many options to show
+    #[arg(long, default_value_t = 5)]
+    top: usize,
+}
+
+#[derive(Args)]
+struct PlayArgs {
+    #[arg(long, default_value_t = 1000)]
+    rounds: usize,
+    /// Policies to compare; all but mcts when left out
+    #[arg(long = "policy", value_enum)]
+    policies: Vec<Policies>,
+    /// Deals the mcts policy searches before each decision
+    #[arg(long, default_value_t = 200)]
+    iterations: usize,
+    /// Chips needed to win; 300 unless set by the scenario
+    #[arg(long)]
+    blind: Option<f64>,
+    #[arg(long)]
+    seed: Option<Seed>,
+    #[arg(long, default_value_t = default_threads())]
+    threads: usize,
+    #[arg(long, value_enum, default_value_t = DeckTypes::Standard)]
+    deck: DeckTypes,
+    /// Start from a scenario file or save.jkr instead of --deck
+    #[arg(long, conflicts_with = "deck")]
+    scenario: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct TournamentArgs {
+    /// Rounds, and runs, each policy plays
+    #[arg(long, default_value_t = 500)]
+    games: usize,
+    /// Policies to enter; all but mcts when left out
+    #[arg(long = "policy", value_enum)]
+    policies: Vec<Policies>,
+    /// Chips needed to win a single round; 300 unless set by the scenario
+    #[arg(long)]
+    blind: Option<f64>,
+    /// Deals the mcts policy searches before each decision
+    #[arg(long, default_value_t = 200)]
+    iterations: usize,
+    #[arg(long)]
+    seed: Option<Seed>,
+    #[arg(long, default_value_t = default_threads())]
+    threads: usize,
+    #[arg(long, value_enum, default_value_t = DeckTypes::Standard)]
+    deck: DeckTypes,
+    /// Start from a scenario file or save.jkr instead of --deck
+    #[arg(long, conflicts_with = "deck")]
+    scenario: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct RecordArgs {
+    #[arg(long, value_enum, default_value_t = Policies::Greedy)]
+    policy: Policies,
+    /// Which game of the seed to play, as numbered by play and tournament
+    #[arg(long, default_value_t = 0)]
+    game: usize,
+    /// Play a whole run instead of a single round
+    #[arg(long)]
+    run: bool,
+    /// Chips needed to win a single round; 300 unless set by the scenario
+    #[arg(long)]
+    blind: Option<f64>,
+    /// Deals the mcts policy searches before each decision
+    #[arg(long, default_value_t = 200)]
+    iterations: usize,
+    #[arg(long)]
+    seed: Option<Seed>,
+    #[arg(long, value_enum, default_value_t = DeckTypes::Standard)]
+    deck: DeckTypes,
+    /// Start from a scenario file or save.jkr instead of --deck
+    #[arg(long, conflicts_with = "deck")]
+    scenario: Option<PathBuf>,
+    /// Write the log here instead of printing it
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct AdviseArgs {
+    #[arg(long)]
+    scenario: PathBuf,
+    /// Chips needed to win; 300 unless set by the scenario
+    #[arg(long)]
+    blind: Option<f64>,
+    /// Deals to search
+    #[arg(long, default_value_t = 5000)]
+    iterations: usize,
+    #[arg(long)]
+    seed: Option<Seed>,
+    /// How many options to show
+    #[arg(long, default_value_t = 5)]
+    top: usize,
+}
+
+#[derive(Args)]
+struct DistributionArgs {
+    #[arg(value_enum)]
+    of: Quantities,
+    /// The policy playing rounds or runs; unused for best-hand
+    #[arg(long, value_enum, default_value_t = Policies::Greedy)]
+    policy: Policies,
+    /// Rounds, runs or opening draws to sample
+    #[arg(long, default_value_t = 1000)]
+    games: usize,
+    /// Chips needed to win a single round; 300 unless set by the scenario
+    #[arg(long)]
+    blind: Option<f64>,
+    /// Deals the mcts policy searches before each decision
+    #[arg(long, default_value_t = 200)]
+    iterations: usize,
+    #[arg(long)]
+    seed: Option<Seed>,
+    #[arg(long, default_value_t = default_threads())]
+    threads: usize,
+    #[arg(long, value_enum, default_value_t = DeckTypes::Standard)]
+    deck: DeckTypes,
+    /// Start from a scenario file or save.jkr instead of --deck
+    #[arg(long, conflicts_with = "deck")]
+    scenario: Option<PathBuf>,
+    /// Histogram bins
+    #[arg(long, default_value_t = 20)]
+    bins: usize,
+    /// Also write the histogram here as an SVG chart
+    #[arg(long)]
+    svg: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct DrawsArgs {
+    /// Hands held to look at: the one dealt, then one after each play or
+    /// discard
+    #[arg(long, default_value_t = 4)]
+    draws: usize,
+    /// Tabulate every discard count from none up to this
+    #[arg(long, default_value_t = 3)]
+    discards: u32,
+    /// The policy choosing the plays and discards
+    #[arg(long, value_enum, default_value_t = Policies::ChaseFlush)]
+    policy: Policies,
+    #[arg(long, default_value_t = 10_000)]
+    rounds: usize,
+    /// Chips that end the round early; never by default, so every draw is seen
+    #[arg(long)]
+    blind: Option<f64>,
+    /// Deals the mcts policy searches before each decision
+    #[arg(long, default_value_t = 200)]
+    iterations: usize,
+    #[arg(long)]
+    seed: Option<Seed>,
+    #[arg(long, default_value_t = default_threads())]
+    threads: usize,
+    #[arg(long, value_enum, default_value_t = DeckTypes::Standard)]
+    deck: DeckTypes,
+    /// Start from a scenario file or save.jkr instead of --deck
+    #[arg(long, conflicts_with = "deck")]
+    scenario: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct SweepArgs {
+    /// Hand sizes to try, e.g. 7,8,10; 8 unless set by the scenario
+    #[arg(long, value_delimiter = ',')]
+    hand_size: Vec<usize>,
+    /// Ranks to take out of the deck, e.g. 2,3,4; repeat for more decks. The
+    /// whole deck is always included
+    #[arg(long)]
+    remove: Vec<Removed>,
+    /// Discard counts to try, e.g. 0,1,3. Above 0 the odds are simulated
+    #[arg(long, value_delimiter = ',', default_value = "0")]
+    discards: Vec<u32>,
+    /// The policy choosing the discards
+    #[arg(long, value_enum, default_value_t = Policies::ChaseFlush)]
+    policy: Policies,
+    /// Rounds per simulated point
+    #[arg(long, default_value_t = 10_000)]
+    rounds: usize,
+    /// Deals the mcts policy searches before each decision
+    #[arg(long, default_value_t = 200)]
+    iterations: usize,
+    #[arg(long)]
+    seed: Option<Seed>,
+    #[arg(long, default_value_t = default_threads())]
+    threads: usize,
+    #[arg(long, value_enum, default_value_t = DeckTypes::Standard)]
+    deck: DeckTypes,
+    /// Start from the whole deck in a scenario file or save.jkr instead of --deck
+    #[arg(long, conflicts_with = "deck")]
+    scenario: Option<PathBuf>,
+    /// Print one CSV line per point and hand type instead of a table
+    #[arg(long)]
+    csv: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Quantities {
+    /// Chips scored in a single round
+    Chips,
+    /// The best score on offer in each opening draw
+    BestHand,
+    /// The ante a whole run reaches
+    Ante,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Policies {
+    Greedy,
+    ChaseFlush,
+    ChaseStraight,
+    Random,
+    /// Tree search, much slower than the rest
+    Mcts,
+}
+
+// The policy picked on the command line, so the commands that play one can be
+// written once rather than once per policy.
+#[derive(Clone)]
+enum AnyPolicy {
+    Greedy(Greedy),
+    ChaseFlush(ChaseFlush),
+    ChaseStraight(ChaseStraight),
+    Random(Random),
+    Mcts(Mcts),
+}
+
+impl AnyPolicy {
+    /// `iterations` is only used by mcts.
+    fn new(policy: Policies, iterations: usize) -> AnyPolicy {
+        match policy {
+            Policies::Greedy => AnyPolicy::Greedy(Greedy),
+            Policies::ChaseFlush => AnyPolicy::ChaseFlush(ChaseFlush),
+            Policies::ChaseStraight => AnyPolicy::ChaseStraight(ChaseStraight),
+            Policies::Random => AnyPolicy::Random(Random),
+            Policies::Mcts => AnyPolicy::Mcts(Mcts::new(iterations)),
+        }
+    }
+}
+
+impl Policy for AnyPolicy {
+    fn name(&self) -> &'static str {
+        match self {
+            AnyPolicy::Greedy(policy) => policy.name(),
+            AnyPolicy::ChaseFlush(policy) => policy.name(),
+            AnyPolicy::ChaseStraight(policy) => policy.name(),
+            AnyPolicy::Random(policy) => policy.name(),
+            AnyPolicy::Mcts(policy) => policy.name(),
+        }
+    }
+
+    fn choose(&mut self, view: &View, rng: &mut ChaCha8Rng) -> Action {
+        match self {
+            AnyPolicy::Greedy(policy) => policy.choose(view, rng),
+            AnyPolicy::ChaseFlush(policy) => policy.choose(view, rng),
+            AnyPolicy::ChaseStraight(policy) => policy.choose(view, rng),
+            AnyPolicy::Random(policy) => policy.choose(view, rng),
+            AnyPolicy::Mcts(policy) => policy.choose(view, rng),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum DeckTypes {
+    Standard,
+    Abandoned,
+    Checkered,
+}
+
+impl DeckTypes {
+    fn deck(self) -> Deck {
+        match self {
+            DeckTypes::Standard => Deck::standard(),
+            DeckTypes::Abandoned => Deck::abandoned(),
+            DeckTypes::Checkered => Deck::checkered(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Intervals {
+    Wilson,
+    ClopperPearson,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Estimators {
+    Plain,
+    /// Stratified by suit split, standard deck only
+    Stratified,
+    /// Importance sampling for Straight Flush and Four OAK, standard deck only
+    Importance,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Formats {
+    Text,
+    Json,
+    Csv,
+}
 
 fn main() {
-    let samples = 100_000usize;
-    let mut straight_flush = 0usize;
-    let mut four_oak = 0usize;
-    let mut full_house = 0usize;
-    let mut flush = 0usize;
-    let mut straight = 0usize;
-    let mut three_oak = 0usize;
-    let mut two_pair = 0usize;
-    let mut pair = 0usize;
-
-    let mut rng = rand::thread_rng();
-    for _ in 0..samples {
-        let mut deck = Deck::standard();
-        deck.cards.shuffle(&mut rng);
-
-        let mut hand = Hand::of_size(8);
-
-        let first_draw = deck.deal_n_cards(8);
-        hand.add_to_hand(first_draw);
-        hand.sort_by_rank_ace_high();
-
-        // let valid_hands = hand.contains();
-
-        // for valid_hand in valid_hands {
-        //     match valid_hand {
-        //         ValidHands::StraightFlush(_, _, _, _, _) => straight_flush += 1,
-        //         ValidHands::FourOAK(_, _, _, _) => four_oak += 1,
-        //         ValidHands::FullHouse(_, _, _, _, _) => full_house += 1,
-        //         ValidHands::Flush(_, _, _, _, _) => flush += 1,
-        //         ValidHands::Straight(_, _, _, _, _) => straight += 1,
-        //         ValidHands::ThreeOAK(_, _, _) => three_oak += 1,
-        //         ValidHands::TwoPair(_, _, _, _) => two_pair += 1,
-        //         ValidHands::Pair(_, _) => pair += 1,
-        //     }
-        // }
-
-        let valid_hand = ValidHands::has_straight(hand.clone());
-        if valid_hand.is_some() {
-            let (v, _) = valid_hand.unwrap();
-            match v {
-                ValidHands::Straight(_, _, _, _, _) => straight += 1,
-                // ValidHands::StraightFlush(_, _, _, _, _) => straight_flush += 1,
-                _ => panic!()
+    match Cli::parse().command {
+        Commands::Simulate(args) => run_simulate(args),
+        Commands::Odds {
+            hand_size,
+            deck,
+            scenario,
+        } => run_odds(hand_size, deck, scenario),
+        Commands::Evaluate { cards, scenario } => run_evaluate(&cards, scenario),
+        Commands::Solve(args) => run_solve(args),
+        Commands::Play(args) => run_play(args),
+        Commands::Tournament(args) => run_tournament(args),
+        Commands::Record(args) => run_record(args),
+        Commands::Replay {
+            log,
+            deck,
+            scenario,
+        } => run_replay(log, deck, scenario),
+        #[cfg(feature = "tui")]
+        Commands::Tui {
+            blind,
+            seed,
+            deck,
+            scenario,
+        } => run_tui(blind, seed, deck, scenario),
+        Commands::Advise(args) => run_advise(args),
+        Commands::Deal {
+            seed,
+            antes,
+            deck,
+            scenario,
+        } => run_deal(seed, antes, deck, scenario),
+        Commands::Distribution(args) => run_distribution(args),
+        Commands::Draws(args) => run_draws(args),
+        Commands::Sweep(args) => run_sweep(args),
+        Commands::Thinning {
+            target,
+            hand_size,
+            deck,
+            scenario,
+            top,
+        } => run_thinning(target, hand_size, deck, scenario, top),
+    }
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{message}");
+    process::exit(1);
+}
+
+fn load_scenario(path: Option<PathBuf>) -> Option<Scenario> {
+    path.map(|path| Scenario::load(&path).unwrap_or_else(|e| exit_with(&e.to_string())))
+}
+
+// The deck and hand size to draw from, from the scenario when there is one.
+// An explicit --hand-size still wins.
+fn draw_setup(
+    deck: DeckTypes,
+    hand_size: Option<usize>,
+    scenario: &Option<Scenario>,
+) -> (Deck, usize) {
+    match scenario {
+        Some(scenario) => (
+            scenario.full_deck(),
+            hand_size.unwrap_or(scenario.hand.size),
+        ),
+        None => (deck.deck(), hand_size.unwrap_or(8)),
+    }
+}
+
+fn run_simulate(args: SimulateArgs) {
+    let standard = args.deck == DeckTypes::Standard && args.scenario.is_none();
+    let scenario = load_scenario(args.scenario);
+    let (deck, hand_size) = draw_setup(args.deck, args.hand_size, &scenario);
+    let mut config = SimConfig::new(args.samples, hand_size);
+    config.deck = deck;
+    config.threads = args.threads;
+    config.confidence = args.confidence;
+    config.interval_method = match args.interval {
+        Intervals::Wilson => IntervalMethods::Wilson,
+        Intervals::ClopperPearson => IntervalMethods::ClopperPearson,
+    };
+    config.target_width = args.target_width;
+    if let Some(seed) = args.seed.or(scenario.and_then(|s| s.seed)) {
+        config.seed = seed;
+    }
+    if hand_size > config.deck.cards.len() {
+        exit_with("hand size is larger than the deck");
+    }
+    if args.estimator != Estimators::Plain && !standard {
+        exit_with("the stratified and importance estimators only support the standard deck");
+    }
+    if args.format == Formats::Text {
+        println!("Seed: {}, Threads: {}", config.seed, config.threads);
+    }
+
+    let report = match args.estimator {
+        Estimators::Plain => {
+            let tally = simulate(&config);
+            if args.format == Formats::Text {
+                output_stats(&tally, &config);
+                return;
             }
-            // println!("\n---HIT---\n");
-            // println!("{}", valid_hand.unwrap().0);
+            Report::from_tally(&tally, &config)
+        }
+        Estimators::Stratified => {
+            Report::from_estimates(&variance::stratified(&config), &config, "stratified")
+        }
+        Estimators::Importance => {
+            let estimates: Vec<_> = [HandTypes::FourOAK, HandTypes::StraightFlush]
+                .into_iter()
+                .filter_map(|t| variance::importance(&config, t).map(|e| (t, e)))
+                .collect();
+            Report::from_estimates(&estimates, &config, "importance")
         }
+    };
+
+    match args.format {
+        Formats::Text => output_estimates(&report),
+        Formats::Json => println!("{}", report.to_json()),
+        Formats::Csv => print!("{}", report.to_csv()),
     }
+}
 
-    let p = (straight as f32 / samples as f32) * 100.;
-    println!("Found Straight in {p}% of first 8 card draw.");
-    // let p = (straight_flush as f32 / samples as f32) * 100.;
-    // println!("Found Straight Flush in {p}% of first 8 card draw.");
+fn output_stats(tally: &Tally, config: &SimConfig) {
+    match config.target_width {
+        Some(width) => println!(
+            "Samples: {} of at most {} (stopped at interval width {width})\n",
+            tally.samples, config.samples
+        ),
+        None => println!("Samples: {}\n", tally.samples),
+    }
 
-    // output_stats(
-    //     samples,
-    //     straight_flush,
-    //     full_house,
-    //     four_oak,
-    //     flush,
-    //     straight,
-    //     three_oak,
-    //     two_pair,
-    //     pair,
-    // );
+    for hand_type in HandTypes::ALL.iter().rev() {
+        let p = tally.frequency(*hand_type) * 100.;
+        let interval = tally.interval(*hand_type, config.interval_method, config.confidence);
+        println!(
+            "Found {} in {p:.3}% ({}% CI {:.3}% - {:.3}%) of first {} card draw.",
+            hand_type.to_str(),
+            config.confidence * 100.,
+            interval.lower * 100.,
+            interval.upper * 100.,
+            config.hand_size,
+        );
+    }
 }
 
-fn output_stats(
-    samples: usize,
-    straight_flush: usize,
-    full_house: usize,
-    four_oak: usize,
-    flush: usize,
-    straight: usize,
-    three_oak: usize,
-    two_pair: usize,
-    pair: usize,
-) {
-    println!("Samples: {samples}\n");
-    let samples = samples as f32;
-    let p = (straight_flush as f32 / samples) * 100.;
-    println!("Found Straight Flush in {p}% of first 8 card draw.");
+fn output_estimates(report: &Report) {
+    for row in report.rows.iter().rev() {
+        println!(
+            "Found {} in {:.3}% (standard error {:.4}%) of first {} card draw.",
+            row.hand_type.to_str(),
+            row.frequency * 100.,
+            row.std_error.unwrap_or_default() * 100.,
+            report.hand_size,
+        );
+    }
+}
 
-    let p = (full_house as f32 / samples) * 100.;
-    println!("Found Full House in {p}% of first 8 card draw.");
+fn run_odds(hand_size: Option<usize>, deck: DeckTypes, scenario: Option<PathBuf>) {
+    let (deck, hand_size) = draw_setup(deck, hand_size, &load_scenario(scenario));
+    if hand_size > deck.cards.len() {
+        exit_with("hand size is larger than the deck");
+    }
+    println!(
+        "Exact odds for a {hand_size} card draw from a {} card deck\n",
+        deck.cards.len()
+    );
+    for (hand_type, p) in odds::exact(&deck, hand_size).iter().rev() {
+        println!("{:>15}: {:.4}%", hand_type.to_str(), p * 100.);
+    }
+}
 
-    let p = (four_oak as f32 / samples) * 100.;
-    println!("Found Four OAK in {p}% of first 8 card draw.");
+fn format_cards(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(Card::to_notation)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
 
-    let p = (flush as f32 / samples) * 100.;
-    println!("Found Flush in {p}% of first 8 card draw.");
+fn run_evaluate(cards: &[Card], scenario: Option<PathBuf>) {
+    let (jokers, levels) = match load_scenario(scenario) {
+        Some(scenario) => (scenario.jokers, scenario.levels),
+        None => (vec![], HandLevels::default()),
+    };
+    let score = score::score_with(cards, &jokers, &levels);
+    println!(
+        "Hand: {} (level {})",
+        score.hand_type.to_str(),
+        levels.level(score.hand_type)
+    );
+    println!("Scoring: {}", format_cards(&score.scoring));
+    println!(
+        "{} chips x {} mult = {}",
+        score.chips,
+        score.mult,
+        score.total()
+    );
+    if !jokers.is_empty() {
+        let jokers: Vec<String> = jokers.iter().map(|j| j.to_notation()).collect();
+        println!("Jokers: {}", jokers.join(", "));
+    }
+}
 
-    let p = (straight as f32 / samples) * 100.;
-    println!("Found Straight in {p}% of first 8 card draw.");
+fn run_solve(args: SolveArgs) {
+    let scenario = load_scenario(args.scenario);
+    let held = match &scenario {
+        Some(scenario) if args.cards.is_empty() => scenario.hand.cards.clone(),
+        _ => args.cards,
+    };
+    if held.is_empty() {
+        exit_with("no held cards given or in the scenario");
+    }
+    // A scenario's hand size bounds the cards held; otherwise they set it.
+    let hand_size = scenario.as_ref().map_or(held.len(), |s| s.hand.size);
+    let mut config = SimConfig::new(args.samples, hand_size);
+    config.deck = match &scenario {
+        Some(scenario) => scenario.full_deck(),
+        None => args.deck.deck(),
+    };
+    config.threads = args.threads;
+    if let Some(seed) = args.seed.or(scenario.and_then(|s| s.seed)) {
+        config.seed = seed;
+    }
+    let objective = match args.target {
+        Some(hand_type) => Objectives::AtLeast(hand_type),
+        None => Objectives::Score,
+    };
+    let ranked = solve::recommend(&held, objective, &config)
+        .unwrap_or_else(|err| exit_with(&format!("can't solve for these cards: {err}")));
+    println!("Seed: {}, Threads: {}\n", config.seed, config.threads);
 
-    let p = (three_oak as f32 / samples) * 100.;
-    println!("Found Three OAK in {p}% of first 8 card draw.");
+    for option in ranked.iter().take(args.top) {
+        let value = match objective {
+            Objectives::Score => format!("{:.1} expected base score", option.value),
+            Objectives::AtLeast(hand_type) => format!(
+                "{:.2}% chance of {} or better",
+                option.value * 100.,
+                hand_type.to_str()
+            ),
+        };
+        let discard = match option.discard.is_empty() {
+            true => "nothing".to_string(),
+            false => format_cards(&option.discard),
+        };
+        println!("Discard {discard}: {value}");
+    }
+}
 
-    let p = (two_pair as f32 / samples) * 100.;
-    println!("Found Two Pair in {p}% of first 8 card draw.");
+// The round to play, from the scenario when there is one. An explicit --blind
+// still wins.
+fn round_setup(deck: DeckTypes, blind: Option<f64>, scenario: &Option<Scenario>) -> RoundSetup {
+    let mut setup = match scenario {
+        Some(scenario) => scenario.round_setup(blind.unwrap_or(300.)),
+        None => RoundSetup::new(deck.deck(), blind.unwrap_or(300.)),
+    };
+    if let Some(blind) = blind {
+        setup.blind = blind;
+    }
+    setup
+}
+
+fn run_play(args: PlayArgs) {
+    let scenario = load_scenario(args.scenario);
+    let setup = round_setup(args.deck, args.blind, &scenario);
+    let seed = args
+        .seed
+        .or(scenario.and_then(|s| s.seed))
+        .unwrap_or_else(Seed::random);
+    let policies = chosen_policies(&args.policies);
 
-    let p = (pair as f32 / samples) * 100.;
-    println!("Found Pair in {p}% of first 8 card draw.");
+    println!("Seed: {seed}, Threads: {}", args.threads);
+    println!(
+        "{} rounds against a {} chip blind\n",
+        args.rounds, setup.blind
+    );
+    for policy in policies {
+        let policy = AnyPolicy::new(policy, args.iterations);
+        let results = play_rounds(&setup, &policy, args.rounds, &seed, args.threads);
+        let summary = Summary::new(&results);
+        println!(
+            "{:>15}: won {:.2}%, {:.0} chips, {:.2} hands and {:.2} discards on average",
+            policy.name(),
+            summary.win_rate() * 100.,
+            summary.mean_chips,
+            summary.mean_hands,
+            summary.mean_discards,
+        );
+    }
+}
+
+// The policies asked for, or every one but the slow mcts.
+fn chosen_policies(policies: &[Policies]) -> Vec<Policies> {
+    match policies.is_empty() {
+        true => Policies::value_variants()
+            .iter()
+            .copied()
+            .filter(|p| *p != Policies::Mcts)
+            .collect(),
+        false => policies.to_vec(),
+    }
+}
+
+fn run_tournament(args: TournamentArgs) {
+    let scenario = load_scenario(args.scenario);
+    let setup = round_setup(args.deck, args.blind, &scenario);
+    let seed = args
+        .seed
+        .or(scenario.and_then(|s| s.seed))
+        .unwrap_or_else(Seed::random);
+    let (games, threads) = (args.games, args.threads);
+    let entries: Vec<Entry> = chosen_policies(&args.policies)
+        .into_iter()
+        .map(|policy| {
+            let policy = AnyPolicy::new(policy, args.iterations);
+            Entry::play(&policy, &setup, games, &seed, threads)
+        })
+        .collect();
+
+    println!("Seed: {seed}, Threads: {threads}");
+    println!(
+        "{games} rounds against a {} chip blind and {games} runs from ante 1\n",
+        setup.blind
+    );
+    for entry in &entries {
+        let standing = Standing::new(entry);
+        let chips = standing.chips_percentiles.map(|c| format!("{c:.0}"));
+        println!(
+            "{:>15}: won {:.2}% of rounds, chips {} (10th to 90th percentile), reached ante {:.2} on average",
+            standing.name,
+            standing.rounds.win_rate() * 100.,
+            chips.join("/"),
+            standing.mean_ante,
+        );
+    }
+
+    println!();
+    for comparison in compare_all(&entries) {
+        println!(
+            "{} vs {}: won {} to {} of the split rounds (p = {:.4}), {:+.1} chips (p = {:.4}), {:+.2} antes (p = {:.4})",
+            comparison.first,
+            comparison.second,
+            comparison.only_first,
+            comparison.only_second,
+            comparison.wins_p,
+            comparison.chips.mean_difference,
+            comparison.chips.p,
+            comparison.ante.mean_difference,
+            comparison.ante.p,
+        );
+    }
+}
+
+fn run_record(args: RecordArgs) {
+    let scenario = load_scenario(args.scenario);
+    let setup = round_setup(args.deck, args.blind, &scenario);
+    let seed = args
+        .seed
+        .or(scenario.and_then(|s| s.seed))
+        .unwrap_or_else(Seed::random);
+    let mut policy = AnyPolicy::new(args.policy, args.iterations);
+    let history = match args.run {
+        true => History::record_run(&setup, &mut policy, &seed, args.game).1,
+        false => History::record_round(&setup, &mut policy, &seed, args.game).1,
+    };
+
+    match args.out {
+        Some(path) => fs::write(&path, history.to_text())
+            .unwrap_or_else(|e| exit_with(&format!("couldn't write {}: {e}", path.display()))),
+        None => print!("{}", history.to_text()),
+    }
+}
+
+fn run_replay(log: PathBuf, deck: DeckTypes, scenario: Option<PathBuf>) {
+    let text = fs::read_to_string(&log)
+        .unwrap_or_else(|e| exit_with(&format!("couldn't read {}: {e}", log.display())));
+    let history = History::parse(&text).unwrap_or_else(|e| exit_with(&e.to_string()));
+    let setup = round_setup(deck, None, &load_scenario(scenario));
+    match history::replay(&setup, &history) {
+        Ok(()) => println!(
+            "Replayed {} events from seed {}, game {}: identical",
+            history.events.len(),
+            history.seed,
+            history.index
+        ),
+        Err(e) => exit_with(&e.to_string()),
+    }
+}
+
+#[cfg(feature = "tui")]
+fn run_tui(blind: Option<f64>, seed: Option<Seed>, deck: DeckTypes, scenario: Option<PathBuf>) {
+    let scenario = load_scenario(scenario);
+    let setup = round_setup(deck, blind, &scenario);
+    let seed = seed
+        .or(scenario.and_then(|s| s.seed))
+        .unwrap_or_else(Seed::random);
+    if let Err(e) = monte::tui::run(setup, seed) {
+        exit_with(&format!("terminal error: {e}"));
+    }
+}
+
+fn run_advise(args: AdviseArgs) {
+    let scenario = load_scenario(Some(args.scenario));
+    let setup = round_setup(DeckTypes::Standard, args.blind, &scenario);
+    let seed = args
+        .seed
+        .or(scenario.and_then(|s| s.seed))
+        .unwrap_or_else(Seed::random);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.to_u64());
+    let round = setup.deal(&mut rng);
+    let view = round.view();
+    if view.hand.is_empty() {
+        exit_with("no held cards in the scenario or left in its deck");
+    }
+
+    println!("Seed: {seed}");
+    println!("Hand: {}", format_cards(view.hand));
+    println!(
+        "{} chips to go with {} hands and {} discards left\n",
+        view.blind - view.chips,
+        view.hands_left,
+        view.discards_left
+    );
+    let picked = |positions: &[usize]| {
+        let cards: Vec<Card> = positions.iter().map(|i| view.hand[*i]).collect();
+        format_cards(&cards)
+    };
+    for option in Mcts::new(args.iterations)
+        .search(&view, &mut rng)
+        .iter()
+        .take(args.top)
+    {
+        let action = match &option.action {
+            Action::Play(positions) => format!("Play {}", picked(positions)),
+            Action::Discard(positions) => format!("Discard {}", picked(positions)),
+        };
+        println!(
+            "{action}: {:.1}% to win over {} deals",
+            option.win_rate * 100.,
+            option.visits
+        );
+    }
+}
+
+fn run_deal(seed: Seed, antes: u32, deck: DeckTypes, scenario: Option<PathBuf>) {
+    let setup = round_setup(deck, None, &load_scenario(scenario));
+    let mut rng = BalatroRng::new(&seed.to_string());
+
+    println!("Seed: {seed}, dealt as the game does when every blind is played");
+    for ante in 1..=antes {
+        println!("\nAnte {ante}, boss {}", rng.next_boss(ante));
+        for blind in ["Small Blind", "Big Blind", "Boss Blind"] {
+            let round = setup.deal_balatro(&mut rng, ante);
+            println!("{blind:>12}: {}", format_cards(round.hand()));
+            let shop: Vec<_> = (0..2)
+                .map(|_| rng.shop_card(ante, &setup.deck).key)
+                .collect();
+            println!("{:>12}  {}", "shop", shop.join(", "));
+        }
+    }
+}
+
+fn run_distribution(args: DistributionArgs) {
+    let scenario = load_scenario(args.scenario);
+    let setup = round_setup(args.deck, args.blind, &scenario);
+    let seed = args
+        .seed
+        .or(scenario.and_then(|s| s.seed))
+        .unwrap_or_else(Seed::random);
+    let (games, threads) = (args.games, args.threads);
+    let policy = AnyPolicy::new(args.policy, args.iterations);
+
+    let (title, samples) = match args.of {
+        Quantities::Chips => {
+            let results = play_rounds(&setup, &policy, games, &seed, threads);
+            (
+                format!("Chips per round, {}", policy.name()),
+                results.iter().map(|r| r.chips).collect(),
+            )
+        }
+        Quantities::BestHand => (
+            "Best score in the opening draw".to_string(),
+            opening_best_scores(&setup, games, &seed, threads),
+        ),
+        Quantities::Ante => {
+            let results: Vec<RunResult> = play_runs(&setup, &policy, games, &seed, threads);
+            (
+                format!("Ante reached, {}", policy.name()),
+                results.iter().map(|r| f64::from(r.ante)).collect(),
+            )
+        }
+    };
+    let distribution = Distribution::new(samples);
+
+    println!("Seed: {seed}, Threads: {threads}");
+    println!("{title} over {} samples\n", distribution.len());
+    println!(
+        "mean {:.2}, variance {:.2}, std dev {:.2}, min {}, max {}",
+        distribution.mean(),
+        distribution.variance(),
+        distribution.std_dev(),
+        distribution.min(),
+        distribution.max(),
+    );
+    let percentiles = [0.05, 0.1, 0.25, 0.5, 0.75, 0.9, 0.95]
+        .map(|q| format!("{:.0}th {:.1}", q * 100., distribution.percentile(q)));
+    println!("percentiles: {}\n", percentiles.join(", "));
+
+    let histogram = distribution.histogram(args.bins);
+    print!("{}", histogram.to_ascii(50));
+    if let Some(path) = args.svg {
+        fs::write(&path, histogram.to_svg(640, 360, &title))
+            .unwrap_or_else(|e| exit_with(&format!("couldn't write {}: {e}", path.display())));
+    }
+}
+
+fn run_draws(args: DrawsArgs) {
+    let scenario = load_scenario(args.scenario);
+    let setup = round_setup(
+        args.deck,
+        Some(args.blind.unwrap_or(f64::INFINITY)),
+        &scenario,
+    );
+    let seed = args
+        .seed
+        .or(scenario.and_then(|s| s.seed))
+        .unwrap_or_else(Seed::random);
+    let (draws, discards, rounds, threads) = (args.draws, args.discards, args.rounds, args.threads);
+    let policy = AnyPolicy::new(args.policy, args.iterations);
+    let tallies = seen_by_discards(&setup, &policy, draws, discards, rounds, &seed, threads);
+
+    println!("Seed: {seed}, Threads: {threads}");
+    println!(
+        "Held within the first {draws} draws of {rounds} rounds of {}\n",
+        policy.name()
+    );
+    let header: Vec<String> = (0..=discards)
+        .map(|d| match d {
+            1 => format!("{:>12}", "1 discard"),
+            _ => format!("{:>12}", format!("{d} discards")),
+        })
+        .collect();
+    println!("{:>15} {}", "", header.join(""));
+    for hand_type in HandTypes::ALL.iter().rev() {
+        let row: Vec<String> = tallies
+            .iter()
+            .map(|tally| format!("{:>11.3}%", tally.frequency(*hand_type) * 100.))
+            .collect();
+        println!("{:>15}:{}", hand_type.to_str(), row.join(""));
+    }
+}
+
+fn run_sweep(args: SweepArgs) {
+    let scenario = load_scenario(args.scenario);
+    let (deck, hand_size) = draw_setup(args.deck, None, &scenario);
+    let seed = args
+        .seed
+        .or(scenario.and_then(|s| s.seed))
+        .unwrap_or_else(Seed::random);
+    let hand_sizes = match args.hand_size.is_empty() {
+        true => vec![hand_size],
+        false => args.hand_size,
+    };
+    let mut removals = vec![Removed::default()];
+    removals.extend(args.remove);
+    let points = Point::grid(&hand_sizes, &removals, &args.discards);
+    if let Some(point) = points
+        .iter()
+        .find(|p| p.hand_size > p.removed.apply(&deck).cards.len())
+    {
+        exit_with(&format!(
+            "hand size {} is larger than the deck",
+            point.hand_size
+        ));
+    }
+
+    let (rounds, threads) = (args.rounds, args.threads);
+    let policy = AnyPolicy::new(args.policy, args.iterations);
+    let sweep = Sweep::run(&deck, &points, &policy, rounds, &seed, threads);
+
+    if args.csv {
+        print!("{}", sweep.to_csv());
+        return;
+    }
+    if points.iter().any(|p| p.discards > 0) {
+        println!("Seed: {seed}, Threads: {threads}");
+        println!(
+            "Points with dN discards are simulated over {rounds} rounds of {}\n",
+            policy.name()
+        );
+    }
+    println!("Columns are hand size, -ranks removed from the deck and dN discards\n");
+    print!("{}", sweep.to_table());
+}
+
+fn run_thinning(
+    target: HandTypes,
+    hand_size: Option<usize>,
+    deck: DeckTypes,
+    scenario: Option<PathBuf>,
+    top: usize,
+) {
+    let (deck, hand_size) = draw_setup(deck, hand_size, &load_scenario(scenario));
+    if hand_size > deck.cards.len() {
+        exit_with("hand size is larger than the deck");
+    }
+    let shown = [
+        target,
+        HandTypes::Flush,
+        HandTypes::Straight,
+        HandTypes::Pair,
+        HandTypes::ThreeOAK,
+        HandTypes::FourOAK,
+    ];
+    let shown: Vec<HandTypes> = shown
+        .iter()
+        .enumerate()
+        .filter(|(i, t)| !shown[..*i].contains(t))
+        .map(|(_, t)| *t)
+        .collect();
+
+    println!(
+        "Exact odds for a {hand_size} card draw from a {} card deck, changes ranked by {}\n",
+        deck.cards.len(),
+        target.to_str()
+    );
+    let header: Vec<String> = shown
+        .iter()
+        .map(|t| format!("{:>15}", t.to_str()))
+        .collect();
+    println!("{:>22} {}", "", header.join(""));
+    let before = thinning::probabilities(&deck, hand_size);
+    let row: Vec<String> = shown
+        .iter()
+        .map(|t| format!("{:>14.3}%", before[*t as usize] * 100.))
+        .collect();
+    println!("{:>22}:{}", "deck as it is", row.join(""));
+
+    let ranking = thinning::ranked(&deck, hand_size, target);
+    let count = match top {
+        0 => ranking.len(),
+        top => top.min(ranking.len()),
+    };
+    for impact in &ranking[..count] {
+        let row: Vec<String> = shown
+            .iter()
+            .map(|t| format!("{:>+14.3}%", impact.delta(*t) * 100.))
+            .collect();
+        println!("{:>22}:{}", impact.change.to_string(), row.join(""));
+    }
 }
@@ -0,0 +1,306 @@
+//! A Monte Carlo tree search player for single rounds, strong enough to
+//! measure the baselines in [`crate::policy`] and human decisions against.
+//!
+//! The order of the draw pile is hidden, so each iteration samples one
+//! ordering of the unseen cards, walks the tree from the current hand
+//! choosing actions by UCB1, then plays the rest of the round out with a
+//! cheap rollout policy. The reward is 1 for beating the blind and 0
+//! otherwise, so an action's mean reward estimates its chance to win.
+//!
+//! Trying every play and discard would spread the iterations over hundreds
+//! of actions, so only a few candidates are searched: the highest scoring
+//! plays, and discards that keep the cards towards a Flush, a Straight,
+//! the best hand held or any pairs.
+
+use std::collections::BTreeMap;
+
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    card::Card,
+    policy::{lowest_first, off_run, off_suit, picks, ChaseFlush, Policy},
+    round::{Action, Round, View},
+    score::{score_with, MAX_PLAYED},
+};
+
+/// Searches `iterations` sampled deals before each decision and takes the
+/// action visited most.
+#[derive(Debug, Clone)]
+pub struct Mcts<R = ChaseFlush> {
+    pub iterations: usize,
+    /// The UCB1 exploration constant. Rewards are 0 or 1, so around 0.7 is
+    /// usual.
+    pub exploration: f64,
+    /// How many of the highest scoring plays are searched.
+    pub plays: usize,
+    /// Plays rounds out past the tree.
+    pub rollout: R,
+}
+
+impl Mcts {
+    pub fn new(iterations: usize) -> Mcts {
+        Mcts {
+            iterations,
+            exploration: 0.7,
+            plays: 4,
+            rollout: ChaseFlush,
+        }
+    }
+}
+
+/// What the search found for one of the actions it tried at the top.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionStats {
+    pub action: Action,
+    pub visits: u32,
+    /// The fraction of the visits that went on to beat the blind.
+    pub win_rate: f64,
+}
+
+// A decision point. Every deal sampled for it holds the same cards, so the
+// candidate actions are the same whichever deal reached it.
+#[derive(Debug, Default)]
+struct Node {
+    visits: u32,
+    edges: Vec<Edge>,
+}
+
+// An action and the decision points it has led to, keyed by the hand drawn.
+#[derive(Debug)]
+struct Edge {
+    action: Action,
+    visits: u32,
+    wins: f64,
+    outcomes: BTreeMap<Vec<Card>, Node>,
+}
+
+impl Edge {
+    fn new(action: Action) -> Edge {
+        Edge {
+            action,
+            visits: 0,
+            wins: 0.,
+            outcomes: BTreeMap::new(),
+        }
+    }
+}
+
+/// The actions the search tries from a hand: the best `plays` plays, each with
+/// only the cards that score, then the discards towards the best hand, a
+/// Flush, a Straight and the pairs held.
+pub fn candidates(view: &View, plays: usize) -> Vec<Action> {
+    let mut scored: Vec<(Vec<usize>, f64)> = vec![];
+    for pick in picks(view.hand.len(), MAX_PLAYED) {
+        let cards: Vec<Card> = pick.iter().map(|i| view.hand[*i]).collect();
+        let score = score_with(&cards, view.jokers, view.levels);
+        // Cards that don't score only matter as a way to cycle the hand,
+        // which the discards cover, so keep the play without them.
+        if score.scoring.len() == cards.len() {
+            scored.push((pick, score.total()));
+        }
+    }
+    // Stable, so equal plays stay fewest cards first.
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let mut actions: Vec<Action> = scored
+        .into_iter()
+        .take(plays.max(1))
+        .map(|(pick, _)| Action::Play(pick))
+        .collect();
+
+    if view.discards_left > 0 {
+        let keep_best = match &actions[0] {
+            Action::Play(best) => (0..view.hand.len()).filter(|i| !best.contains(i)).collect(),
+            Action::Discard(_) => vec![],
+        };
+        let unpaired = (0..view.hand.len())
+            .filter(|i| {
+                let rank = view.hand[*i].rank;
+                view.hand.iter().filter(|c| c.rank == rank).count() < 2
+            })
+            .collect();
+        for unwanted in [keep_best, off_suit(view), off_run(view), unpaired] {
+            let mut discard = lowest_first(view, unwanted);
+            discard.sort_unstable();
+            let discard = Action::Discard(discard);
+            if discard != Action::Discard(vec![]) && !actions.contains(&discard) {
+                actions.push(discard);
+            }
+        }
+    }
+    actions
+}
+
+impl<R: Policy + Clone> Mcts<R> {
+    /// Runs the search from the view and reports on each candidate action,
+    /// most visited first.
+    pub fn search(&self, view: &View, rng: &mut ChaCha8Rng) -> Vec<ActionStats> {
+        let mut root = Node::default();
+        for _ in 0..self.iterations {
+            let mut round = view.sample_round(rng);
+            self.visit(&mut root, &mut round, rng);
+        }
+        let mut stats: Vec<ActionStats> = root
+            .edges
+            .into_iter()
+            .map(|edge| ActionStats {
+                action: edge.action,
+                visits: edge.visits,
+                win_rate: edge.wins / f64::from(edge.visits.max(1)),
+            })
+            .collect();
+        stats.sort_by(|a, b| {
+            b.visits
+                .cmp(&a.visits)
+                .then(b.win_rate.total_cmp(&a.win_rate))
+        });
+        stats
+    }
+
+    // One iteration down the tree from `node`, returning the reward.
+    fn visit(&self, node: &mut Node, round: &mut Round, rng: &mut ChaCha8Rng) -> f64 {
+        if round.is_over() {
+            return if round.won() { 1. } else { 0. };
+        }
+        if node.edges.is_empty() {
+            node.edges = candidates(&round.view(), self.plays)
+                .into_iter()
+                .map(Edge::new)
+                .collect();
+        }
+
+        let ln_visits = f64::from(node.visits.max(1)).ln();
+        let ucb = |edge: &Edge| match edge.visits {
+            0 => f64::INFINITY,
+            n => {
+                let n = f64::from(n);
+                edge.wins / n + self.exploration * (ln_visits / n).sqrt()
+            }
+        };
+        // The first of equals, so unvisited actions are tried in order.
+        let mut chosen = 0;
+        for (i, edge) in node.edges.iter().enumerate() {
+            if ucb(edge) > ucb(&node.edges[chosen]) {
+                chosen = i;
+            }
+        }
+        let edge = &mut node.edges[chosen];
+
+        round
+            .apply(&edge.action)
+            .expect("candidates are legal in every deal of a node");
+        let reward = if edge.visits == 0 {
+            self.roll_out(round, rng)
+        } else {
+            let mut drawn = round.hand().to_vec();
+            drawn.sort();
+            let child = edge.outcomes.entry(drawn).or_default();
+            self.visit(child, round, rng)
+        };
+
+        edge.visits += 1;
+        edge.wins += reward;
+        node.visits += 1;
+        reward
+    }
+
+    fn roll_out(&self, round: &mut Round, rng: &mut ChaCha8Rng) -> f64 {
+        let mut rollout = self.rollout.clone();
+        while !round.is_over() {
+            let action = rollout.choose(&round.view(), rng);
+            if round.apply(&action).is_err() {
+                break;
+            }
+        }
+        if round.won() {
+            1.
+        } else {
+            0.
+        }
+    }
+}
+
+impl<R: Policy + Clone> Policy for Mcts<R> {
+    fn name(&self) -> &'static str {
+        "mcts"
+    }
+
+    fn choose(&mut self, view: &View, rng: &mut ChaCha8Rng) -> Action {
+        match self.search(view, rng).into_iter().next() {
+            Some(best) => best.action,
+            None => self.rollout.choose(view, rng),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{
+        card::Card,
+        deck::Deck,
+        policy::Policy,
+        round::{Action, RoundSetup},
+    };
+
+    use super::{candidates, Mcts};
+
+    // One hand and one discard left, against a blind only a Flush reaches.
+    fn setup() -> RoundSetup {
+        let held: Vec<Card> = "2H 7H 9H KH 4C 4S JD QS"
+            .split(' ')
+            .map(|c| c.parse().unwrap())
+            .collect();
+        let mut deck = Deck::standard();
+        deck.cards.retain(|c| !held.contains(c));
+        let mut setup = RoundSetup::new(deck, 250.);
+        setup.held = held;
+        setup.hands = 1;
+        setup.discards = 1;
+        setup
+    }
+
+    #[test]
+    fn candidates_cover_plays_and_each_draw() {
+        let round = setup().deal(&mut ChaCha8Rng::seed_from_u64(0));
+        let actions = candidates(&round.view(), 2);
+        assert_eq!(
+            actions,
+            [
+                Action::Play(vec![4, 5]),
+                // The King on its own.
+                Action::Play(vec![3]),
+                // Keeping the Pair.
+                Action::Discard(vec![0, 1, 2, 6, 7]),
+                // Keeping the Hearts.
+                Action::Discard(vec![4, 5, 6, 7]),
+                // Keeping 9 J Q K.
+                Action::Discard(vec![0, 1, 4, 5]),
+            ]
+        );
+    }
+
+    #[test]
+    fn discards_towards_the_only_winning_hand() {
+        let setup = setup();
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let round = setup.deal(&mut rng);
+        let mut mcts = Mcts::new(200);
+
+        let stats = mcts.search(&round.view(), &mut rng);
+        assert_eq!(stats[0].action, Action::Discard(vec![4, 5, 6, 7]));
+        // Four draws from 44 cards with 9 Hearts make the Flush about 60% of
+        // the time.
+        assert!((0.4..0.8).contains(&stats[0].win_rate), "{stats:?}");
+        // Playing now can't win.
+        for play in stats.iter().filter(|s| matches!(s.action, Action::Play(_))) {
+            assert_eq!(play.win_rate, 0.);
+        }
+
+        assert_eq!(
+            mcts.choose(&round.view(), &mut rng),
+            Action::Discard(vec![4, 5, 6, 7])
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Enhancements, editions and seals that can be applied to playing cards. Each
+//! is written as a `+name` suffix in card notation, e.g. "KH+glass+foil+redseal".
+
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Enhancements {
+    Bonus,
+    Mult,
+    Wild,
+    Glass,
+    Steel,
+    Stone,
+    Gold,
+    Lucky,
+}
+
+impl Enhancements {
+    pub const ALL: [Enhancements; 8] = [
+        Enhancements::Bonus,
+        Enhancements::Mult,
+        Enhancements::Wild,
+        Enhancements::Glass,
+        Enhancements::Steel,
+        Enhancements::Stone,
+        Enhancements::Gold,
+        Enhancements::Lucky,
+    ];
+
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Enhancements::Bonus => "bonus",
+            Enhancements::Mult => "mult",
+            Enhancements::Wild => "wild",
+            Enhancements::Glass => "glass",
+            Enhancements::Steel => "steel",
+            Enhancements::Stone => "stone",
+            Enhancements::Gold => "gold",
+            Enhancements::Lucky => "lucky",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Editions {
+    Foil,
+    Holographic,
+    Polychrome,
+}
+
+impl Editions {
+    pub const ALL: [Editions; 3] = [Editions::Foil, Editions::Holographic, Editions::Polychrome];
+
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Editions::Foil => "foil",
+            Editions::Holographic => "holo",
+            Editions::Polychrome => "poly",
+        }
+    }
+
+    /// Reads an edition's notation token or its full name, e.g. "holo" or
+    /// "holographic", in any case.
+    pub fn from_name(name: &str) -> Option<Editions> {
+        let name = name.to_ascii_lowercase();
+        Editions::ALL
+            .into_iter()
+            .find(|e| e.to_str() == name || format!("{e:?}").to_ascii_lowercase() == name)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Seals {
+    Gold,
+    Red,
+    Blue,
+    Purple,
+}
+
+impl Seals {
+    pub const ALL: [Seals; 4] = [Seals::Gold, Seals::Red, Seals::Blue, Seals::Purple];
+
+    // Suffixed with "seal" so a Gold Seal can't be mistaken for a Gold card.
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Seals::Gold => "goldseal",
+            Seals::Red => "redseal",
+            Seals::Blue => "blueseal",
+            Seals::Purple => "purpleseal",
+        }
+    }
+}
@@ -0,0 +1,460 @@
+//! Exact probabilities that a draw contains each hand type, for any deck.
+//!
+//! Rather than enumerating draws, each hand type is counted with a dynamic
+//! program that walks the ranks (or suits) once and only remembers what the
+//! hand type depends on: how many of each rank were drawn for the n-of-a-kinds,
+//! the current run of ranks for straights, the cards per suit for flushes and
+//! the run within each suit for straight flushes. Cards are counted as
+//! physically distinct, so duplicates in a deck (e.g. the Checkered Deck) are
+//! weighted correctly. Cards without a rank are never part of a hand but still
+//! take up a slot in the draw.
+
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{deck::Deck, stats::choose, suit::Ranks, valid_hands::HandTypes};
+
+// Ranks in the order the programs walk them. The Ace is visited first as a low
+// card and once more at the end as a high card, without drawing again.
+const LOW_TO_HIGH: [usize; 12] = [2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+const ACE: usize = 14;
+const STRAIGHT_LENGTH: u8 = 5;
+
+// How many copies of each rank and suit the deck holds, indexed by
+// [value - 2][suit].
+struct Composition {
+    counts: [[usize; 4]; 13],
+    unranked: usize,
+}
+
+impl Composition {
+    fn of(deck: &Deck) -> Composition {
+        let mut composition = Composition {
+            counts: [[0; 4]; 13],
+            unranked: 0,
+        };
+        for card in &deck.cards {
+            if card.rank == Ranks::Blank || card.suit as usize >= 4 {
+                composition.unranked += 1;
+            } else {
+                composition.counts[card.value - 2][card.suit as usize] += 1;
+            }
+        }
+        composition
+    }
+
+    fn rank(&self, value: usize) -> [usize; 4] {
+        self.counts[value - 2]
+    }
+
+    fn size(&self) -> usize {
+        self.counts.iter().flatten().sum::<usize>() + self.unranked
+    }
+}
+
+// One step of a dynamic program: `extend` emits every state reachable from a
+// state along with the number of ways to reach it, and equal states are merged.
+fn step<S, F>(states: HashMap<S, f64>, mut extend: F) -> HashMap<S, f64>
+where
+    S: Eq + Hash + Copy,
+    F: FnMut(S, &mut dyn FnMut(S, f64)),
+{
+    let mut next = HashMap::new();
+    for (state, ways) in states {
+        extend(state, &mut |new_state, new_ways| {
+            *next.entry(new_state).or_insert(0.) += ways * new_ways;
+        });
+    }
+    next
+}
+
+// Every way of drawing some copies from each suit of one rank, as
+// (cards per suit, number of ways).
+fn suit_draws(available: [usize; 4], limit: usize) -> Vec<([usize; 4], f64)> {
+    let mut draws = vec![([0; 4], 1.)];
+    for (suit, copies) in available.iter().enumerate() {
+        let mut extended = vec![];
+        for (drawn, ways) in &draws {
+            let used: usize = drawn.iter().sum();
+            for k in 0..=(*copies).min(limit - used) {
+                let mut drawn = *drawn;
+                drawn[suit] = k;
+                extended.push((drawn, ways * choose(*copies, k)));
+            }
+        }
+        draws = extended;
+    }
+    draws
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+struct RankState {
+    cards: usize,
+    run: u8,
+    ace: bool,
+    straight: bool,
+    // Ranks held exactly twice (capped at 2), exactly three and four times.
+    exactly_two: u8,
+    exactly_three: bool,
+    exactly_four: bool,
+    // Ranks held at least twice (capped at 2) and at least three times.
+    at_least_two: u8,
+    at_least_three: bool,
+}
+
+impl RankState {
+    fn with_rank(mut self, drawn: usize) -> RankState {
+        self.cards += drawn;
+        self.run = if drawn > 0 {
+            (self.run + 1).min(STRAIGHT_LENGTH)
+        } else {
+            0
+        };
+        self.straight |= self.run == STRAIGHT_LENGTH;
+        match drawn {
+            2 => self.exactly_two = (self.exactly_two + 1).min(2),
+            3 => self.exactly_three = true,
+            4 => self.exactly_four = true,
+            _ => {}
+        }
+        if drawn >= 2 {
+            self.at_least_two = (self.at_least_two + 1).min(2);
+        }
+        self.at_least_three |= drawn >= 3;
+        self
+    }
+
+    fn contains(&self, hand_type: HandTypes) -> bool {
+        match hand_type {
+            HandTypes::Pair => self.exactly_two > 0,
+            HandTypes::TwoPair => self.at_least_two >= 2,
+            HandTypes::ThreeOAK => self.exactly_three,
+            HandTypes::Straight => self.straight,
+            HandTypes::FullHouse => self.at_least_three && self.exactly_two > 0,
+            HandTypes::FourOAK => self.exactly_four,
+            _ => false,
+        }
+    }
+}
+
+fn rank_states(composition: &Composition, hand_size: usize) -> HashMap<RankState, f64> {
+    let rank_total = |value| composition.rank(value).iter().sum::<usize>();
+    let extend = |total: usize, value_is_ace: bool| {
+        move |state: RankState, emit: &mut dyn FnMut(RankState, f64)| {
+            for drawn in 0..=total.min(hand_size - state.cards) {
+                let mut next = state.with_rank(drawn);
+                if value_is_ace {
+                    next.ace = drawn > 0;
+                }
+                emit(next, choose(total, drawn));
+            }
+        }
+    };
+
+    let mut states = HashMap::from([(RankState::default(), 1.)]);
+    states = step(states, extend(rank_total(ACE), true));
+    for value in LOW_TO_HIGH {
+        states = step(states, extend(rank_total(value), false));
+    }
+    // The Ace again as the top of a straight. Its copies were already counted,
+    // so this only extends the run and leaves the multiples alone.
+    step(states, |mut state, emit| {
+        if state.ace {
+            state.run = (state.run + 1).min(STRAIGHT_LENGTH);
+            state.straight |= state.run == STRAIGHT_LENGTH;
+        }
+        emit(state, 1.)
+    })
+}
+
+// (cards, flush found)
+fn flush_states(composition: &Composition, hand_size: usize) -> HashMap<(usize, bool), f64> {
+    let mut states = HashMap::from([((0, false), 1.)]);
+    for suit in 0..4 {
+        let total: usize = composition.counts.iter().map(|rank| rank[suit]).sum();
+        states = step(states, |(cards, flush), emit| {
+            for drawn in 0..=total.min(hand_size - cards) {
+                emit((cards + drawn, flush || drawn >= 5), choose(total, drawn));
+            }
+        });
+    }
+    states
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+struct SuitRunState {
+    cards: usize,
+    runs: [u8; 4],
+    aces: [bool; 4],
+    found: bool,
+}
+
+impl SuitRunState {
+    fn with_rank(mut self, drawn: [usize; 4]) -> SuitRunState {
+        self.cards += drawn.iter().sum::<usize>();
+        if self.found {
+            return self;
+        }
+        for (run, count) in self.runs.iter_mut().zip(drawn) {
+            *run = if count > 0 {
+                (*run + 1).min(STRAIGHT_LENGTH)
+            } else {
+                0
+            };
+        }
+        self.found = self.runs.contains(&STRAIGHT_LENGTH);
+        self.settle()
+    }
+
+    // Once a straight flush is found the runs no longer matter, so collapse
+    // them to keep the number of states down.
+    fn settle(mut self) -> SuitRunState {
+        if self.found {
+            self.runs = [0; 4];
+            self.aces = [false; 4];
+        }
+        self
+    }
+}
+
+fn straight_flush_states(
+    composition: &Composition,
+    hand_size: usize,
+) -> HashMap<SuitRunState, f64> {
+    let mut states = HashMap::from([(SuitRunState::default(), 1.)]);
+    let aces = composition.rank(ACE);
+    states = step(states, |state: SuitRunState, emit| {
+        for (drawn, ways) in suit_draws(aces, hand_size - state.cards) {
+            let mut next = state.with_rank(drawn);
+            next.aces = drawn.map(|count| count > 0);
+            emit(next.settle(), ways);
+        }
+    });
+    for value in LOW_TO_HIGH {
+        let available = composition.rank(value);
+        states = step(states, |state: SuitRunState, emit| {
+            for (drawn, ways) in suit_draws(available, hand_size - state.cards) {
+                emit(state.with_rank(drawn), ways);
+            }
+        });
+    }
+    step(states, |state: SuitRunState, emit| {
+        let aces = state.aces.map(usize::from);
+        let cards = state.cards;
+        let mut next = state.with_rank(aces);
+        next.cards = cards;
+        emit(next, 1.)
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+struct HighCardState {
+    cards: usize,
+    suits: [usize; 4],
+    run: u8,
+    ace: bool,
+    // A rank held five or more times isn't an n-of-a-kind, but a second one
+    // would make two pair.
+    repeated: bool,
+}
+
+// Draws that contain none of the other hand types: no suit reaches five cards,
+// no run reaches five ranks and no rank is held two to four times.
+fn high_card_states(composition: &Composition, hand_size: usize) -> HashMap<HighCardState, f64> {
+    let extend = |available: [usize; 4], value_is_ace: bool| {
+        move |state: HighCardState, emit: &mut dyn FnMut(HighCardState, f64)| {
+            for (drawn, ways) in suit_draws(available, hand_size - state.cards) {
+                let total: usize = drawn.iter().sum();
+                if (2..=4).contains(&total) || (total >= 5 && state.repeated) {
+                    continue;
+                }
+                let mut next = state;
+                next.cards += total;
+                next.repeated |= total >= 5;
+                for (held, count) in next.suits.iter_mut().zip(drawn) {
+                    *held += count;
+                }
+                if next.suits.iter().any(|held| *held >= 5) {
+                    continue;
+                }
+                next.run = if total > 0 { next.run + 1 } else { 0 };
+                if next.run >= STRAIGHT_LENGTH {
+                    continue;
+                }
+                if value_is_ace {
+                    next.ace = total > 0;
+                }
+                emit(next, ways);
+            }
+        }
+    };
+
+    let mut states = HashMap::from([(HighCardState::default(), 1.)]);
+    states = step(states, extend(composition.rank(ACE), true));
+    for value in LOW_TO_HIGH {
+        states = step(states, extend(composition.rank(value), false));
+    }
+    step(states, |state, emit| {
+        if !(state.ace && state.run + 1 >= STRAIGHT_LENGTH) {
+            emit(state, 1.)
+        }
+    })
+}
+
+/// Probability that `hand_size` cards drawn from `deck` contain each hand type,
+/// with the same meaning as the simulator's Tally: a draw with a full house
+/// counts towards Pair, Three OAK and Two Pair as well. HighCard is the chance
+/// of none of the others.
+pub fn exact(deck: &Deck, hand_size: usize) -> Vec<(HandTypes, f64)> {
+    let composition = Composition::of(deck);
+    let draws = choose(composition.size(), hand_size);
+    if hand_size > composition.size() {
+        return HandTypes::ALL.iter().map(|t| (*t, 0.)).collect();
+    }
+
+    // Weight of a state holding `cards` ranked cards, filling the rest of the
+    // draw with unranked ones.
+    let fill = |cards: usize| choose(composition.unranked, hand_size - cards) / draws;
+
+    let ranks = rank_states(&composition, hand_size);
+    let flushes = flush_states(&composition, hand_size);
+    let straight_flushes = straight_flush_states(&composition, hand_size);
+    let high_cards = high_card_states(&composition, hand_size);
+
+    HandTypes::ALL
+        .iter()
+        .map(|hand_type| {
+            let p = match hand_type {
+                HandTypes::HighCard => high_cards
+                    .iter()
+                    .filter(|_| hand_size > 0)
+                    .map(|(state, ways)| ways * fill(state.cards))
+                    .sum(),
+                HandTypes::Flush => flushes
+                    .iter()
+                    .filter(|((_, flush), _)| *flush)
+                    .map(|((cards, _), ways)| ways * fill(*cards))
+                    .sum(),
+                HandTypes::StraightFlush => straight_flushes
+                    .iter()
+                    .filter(|(state, _)| state.found)
+                    .map(|(state, ways)| ways * fill(state.cards))
+                    .sum(),
+                _ => ranks
+                    .iter()
+                    .filter(|(state, _)| state.contains(*hand_type))
+                    .map(|(state, ways)| ways * fill(state.cards))
+                    .sum(),
+            };
+            (*hand_type, p)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        card::Card,
+        deck::Deck,
+        hand::Hand,
+        seed::Seed,
+        simulation::{simulate, SimConfig},
+        stats::choose,
+        suit::Suits,
+        valid_hands::HandTypes,
+    };
+
+    use super::exact;
+
+    fn probability(odds: &[(HandTypes, f64)], hand_type: HandTypes) -> f64 {
+        odds.iter().find(|(t, _)| *t == hand_type).unwrap().1
+    }
+
+    #[test]
+    fn matches_five_card_poker_counts() {
+        let odds = exact(&Deck::standard(), 5);
+        let total = choose(52, 5);
+        for (hand_type, count) in [
+            (HandTypes::HighCard, 1302540.),
+            (HandTypes::Pair, 1225536.),
+            (HandTypes::TwoPair, 127296.),
+            (HandTypes::ThreeOAK, 58656.),
+            (HandTypes::Straight, 10240.),
+            (HandTypes::Flush, 5148.),
+            (HandTypes::FullHouse, 3744.),
+            (HandTypes::FourOAK, 624.),
+            (HandTypes::StraightFlush, 40.),
+        ] {
+            let p = probability(&odds, hand_type);
+            assert!(
+                (p * total - count).abs() < 1e-6,
+                "{hand_type:?}: {} vs {count}",
+                p * total
+            );
+        }
+    }
+
+    // Every draw from a small deck with duplicates, checked with the detectors.
+    #[test]
+    fn matches_enumeration_with_duplicates() {
+        let mut cards: Vec<Card> = (2..=7)
+            .map(|v| Card::from_card_value(v, Some(Suits::Spades)))
+            .collect();
+        cards.extend([14, 2, 3, 4, 5].map(|v| Card::from_card_value(v, Some(Suits::Hearts))));
+        cards.extend([
+            Card::from_card_value(4, Some(Suits::Spades)),
+            Card::from_card_value(4, Some(Suits::Clubs)),
+            Card::from_card_value(13, Some(Suits::Clubs)),
+            Card::from_card_value(13, Some(Suits::Diamonds)),
+        ]);
+        let hand_size = 7;
+
+        let mut counts = [0usize; 9];
+        let n = cards.len();
+        for mask in 0u32..1 << n {
+            if mask.count_ones() as usize != hand_size {
+                continue;
+            }
+            let hand = Hand {
+                cards: (0..n)
+                    .filter(|i| mask >> i & 1 == 1)
+                    .map(|i| cards[i])
+                    .collect(),
+                size: hand_size,
+            };
+            for hand_type in HandTypes::ALL {
+                if hand_type.detect(&hand) {
+                    counts[hand_type as usize] += 1;
+                }
+            }
+        }
+
+        let odds = exact(&Deck { cards }, hand_size);
+        let total = choose(n, hand_size);
+        for hand_type in HandTypes::ALL {
+            let p = probability(&odds, hand_type);
+            assert!(
+                (p * total - counts[hand_type as usize] as f64).abs() < 1e-6,
+                "{hand_type:?}: {} vs {}",
+                p * total,
+                counts[hand_type as usize]
+            );
+        }
+    }
+
+    #[test]
+    fn agrees_with_simulation_on_checkered_deck() {
+        let mut config = SimConfig::new(20_000, 8);
+        config.deck = Deck::checkered();
+        config.seed = Seed::Numeric(3);
+        let tally = simulate(&config);
+
+        for (hand_type, p) in exact(&config.deck, 8) {
+            let se = (p * (1. - p) / tally.samples as f64).sqrt().max(1e-4);
+            assert!(
+                (tally.frequency(hand_type) - p).abs() < 5. * se,
+                "{hand_type:?}: {} vs {p}",
+                tally.frequency(hand_type)
+            );
+        }
+    }
+}
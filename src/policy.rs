@@ -0,0 +1,267 @@
+//! Decision makers for [`crate::round`], and the baseline strategies other
+//! agents are measured against.
+
+use rand::{seq::index::sample, Rng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    card::Card,
+    round::{Action, View},
+    score::{score_with, Score, MAX_PLAYED},
+    solve::MAX_DISCARD,
+    suit::{Ranks, Suits},
+    valid_hands::HandTypes,
+};
+
+/// Chooses what to do with the held cards. `rng` is the round's, for policies
+/// that make random choices.
+pub trait Policy {
+    fn name(&self) -> &'static str;
+    fn choose(&mut self, view: &View, rng: &mut ChaCha8Rng) -> Action;
+}
+
+/// Every way of picking 1 to `max` of `n` cards, as positions, fewest first.
+pub fn picks(n: usize, max: usize) -> Vec<Vec<usize>> {
+    let mut picks: Vec<Vec<usize>> = (1u32..1 << n)
+        .filter(|mask| mask.count_ones() as usize <= max)
+        .map(|mask| (0..n).filter(|i| mask >> i & 1 == 1).collect())
+        .collect();
+    picks.sort_by_key(Vec::len);
+    picks
+}
+
+/// The highest scoring play from the held cards. Ties go to the play with
+/// more cards, which sends the extra cards away without using a discard.
+pub fn best_play(view: &View) -> (Vec<usize>, Score) {
+    let mut best: Option<(Vec<usize>, Score)> = None;
+    for pick in picks(view.hand.len(), MAX_PLAYED) {
+        let cards: Vec<Card> = pick.iter().map(|i| view.hand[*i]).collect();
+        let score = score_with(&cards, view.jokers, view.levels);
+        if best
+            .as_ref()
+            .is_none_or(|(_, b)| score.total() >= b.total())
+        {
+            best = Some((pick, score));
+        }
+    }
+    best.expect("a round always has held cards while it's being played")
+}
+
+// Plays the best hand if it's already good enough, otherwise None so the
+// caller can chase something better.
+fn settle(view: &View, good_enough: HandTypes) -> Option<Action> {
+    let (pick, score) = best_play(view);
+    let settled = view.discards_left == 0
+        || score.hand_type >= good_enough
+        || view.chips + score.total() >= view.blind;
+    settled.then_some(Action::Play(pick))
+}
+
+/// Up to MAX_DISCARD of the positions, lowest cards first.
+pub fn lowest_first(view: &View, mut unwanted: Vec<usize>) -> Vec<usize> {
+    unwanted.sort_by_key(|i| view.hand[*i].value);
+    unwanted.truncate(MAX_DISCARD);
+    unwanted
+}
+
+// Discards the unwanted cards, or plays the best hand if there's nothing to
+// throw away.
+fn discard_or_play(view: &View, unwanted: Vec<usize>) -> Action {
+    if unwanted.is_empty() {
+        return Action::Play(best_play(view).0);
+    }
+    Action::Discard(lowest_first(view, unwanted))
+}
+
+/// Positions of the cards outside the most held suit.
+pub fn off_suit(view: &View) -> Vec<usize> {
+    let held = |suit: Suits| view.hand.iter().filter(|c| c.suit == suit).count();
+    // max_by_key keeps the last of equals, so ties go to Spades, then Hearts,
+    // then Clubs.
+    let suit = [Suits::Diamonds, Suits::Clubs, Suits::Hearts, Suits::Spades]
+        .into_iter()
+        .max_by_key(|suit| held(*suit))
+        .unwrap();
+    (0..view.hand.len())
+        .filter(|i| view.hand[*i].suit != suit)
+        .collect()
+}
+
+/// Positions of the cards that don't help towards the five rank run held most
+/// of, keeping one card per rank.
+pub fn off_run(view: &View) -> Vec<usize> {
+    // Bit v - 1 for value v, with the Ace at both ends. Blank cards have none.
+    let ranks = |card: &Card| match card.rank {
+        Ranks::Blank => 0,
+        _ => (1u16 << (card.value - 1)) | (1 << (card.alt_value - 1)),
+    };
+    let held: u16 = view.hand.iter().map(ranks).fold(0, |a, b| a | b);
+    // Runs from Ace-low up to Ace-high; later runs win ties.
+    let run = (0..10)
+        .map(|low| 0b11111u16 << low)
+        .max_by_key(|run| (held & run).count_ones())
+        .unwrap();
+
+    let mut kept = 0u16;
+    (0..view.hand.len())
+        .filter(|i| {
+            let bits = ranks(&view.hand[*i]) & run & !kept;
+            // Keep only the lowest bit so an Ace fills one end of the run.
+            let bit = bits & bits.wrapping_neg();
+            kept |= bit;
+            bit == 0
+        })
+        .collect()
+}
+
+/// Always plays the highest scoring hand it holds and never discards.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Greedy;
+
+impl Policy for Greedy {
+    fn name(&self) -> &'static str {
+        "greedy"
+    }
+
+    fn choose(&mut self, view: &View, _: &mut ChaCha8Rng) -> Action {
+        Action::Play(best_play(view).0)
+    }
+}
+
+/// Discards everything outside its most held suit until it holds a Flush or
+/// better, or the best hand would beat the blind anyway.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChaseFlush;
+
+impl Policy for ChaseFlush {
+    fn name(&self) -> &'static str {
+        "chase-flush"
+    }
+
+    fn choose(&mut self, view: &View, _: &mut ChaCha8Rng) -> Action {
+        if let Some(play) = settle(view, HandTypes::Flush) {
+            return play;
+        }
+        discard_or_play(view, off_suit(view))
+    }
+}
+
+/// Keeps one card of each rank in the five rank run it holds most of and
+/// discards the rest until it holds a Straight or better, or the best hand
+/// would beat the blind anyway.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChaseStraight;
+
+impl Policy for ChaseStraight {
+    fn name(&self) -> &'static str {
+        "chase-straight"
+    }
+
+    fn choose(&mut self, view: &View, _: &mut ChaCha8Rng) -> Action {
+        if let Some(play) = settle(view, HandTypes::Straight) {
+            return play;
+        }
+        discard_or_play(view, off_run(view))
+    }
+}
+
+/// Plays or discards (when it can) a random one to five cards, evenly.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Random;
+
+impl Policy for Random {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn choose(&mut self, view: &View, rng: &mut ChaCha8Rng) -> Action {
+        let count = rng.gen_range(1..=view.hand.len().min(MAX_PLAYED));
+        let pick = sample(rng, view.hand.len(), count).into_vec();
+        if view.discards_left > 0 && rng.gen_bool(0.5) {
+            Action::Discard(pick)
+        } else {
+            Action::Play(pick)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{
+        card::Card,
+        deck::Deck,
+        round::{play_rounds, Action, RoundSetup, Summary},
+        seed::Seed,
+    };
+
+    use super::{picks, ChaseFlush, ChaseStraight, Greedy, Policy, Random};
+
+    fn choose(policy: &mut dyn Policy, held: &str) -> Action {
+        let mut setup = RoundSetup::new(Deck { cards: vec![] }, 1e9);
+        setup.held = held
+            .split(' ')
+            .map(|c| c.parse::<Card>().unwrap())
+            .collect();
+        let round = setup.deal(&mut ChaCha8Rng::seed_from_u64(0));
+        policy.choose(&round.view(), &mut ChaCha8Rng::seed_from_u64(0))
+    }
+
+    #[test]
+    fn picks_every_subset() {
+        assert_eq!(picks(8, 5).len(), 8 + 28 + 56 + 70 + 56);
+        assert_eq!(
+            picks(3, 5),
+            [
+                vec![0],
+                vec![1],
+                vec![2],
+                vec![0, 1],
+                vec![0, 2],
+                vec![1, 2],
+                vec![0, 1, 2]
+            ]
+        );
+    }
+
+    #[test]
+    fn baselines_choose_as_described() {
+        let held = "2H 7H 9H KH 4C 4S JD QS";
+        // Cards that don't score ride along with the Pair for free.
+        assert_eq!(choose(&mut Greedy, held), Action::Play(vec![3, 4, 5, 6, 7]));
+        assert_eq!(
+            choose(&mut ChaseFlush, held),
+            Action::Discard(vec![4, 5, 6, 7])
+        );
+        // The 9, J, Q and K are four of the 9 to K run.
+        assert_eq!(
+            choose(&mut ChaseStraight, held),
+            Action::Discard(vec![0, 4, 5, 1])
+        );
+
+        // With a Flush held, the chaser just plays it.
+        let flush = "2H 7H 9H KH 4H 4S JD QS";
+        assert_eq!(
+            choose(&mut ChaseFlush, flush),
+            Action::Play(vec![0, 1, 2, 3, 4])
+        );
+    }
+
+    #[test]
+    fn policies_are_compared_on_the_same_deals() {
+        let setup = RoundSetup::new(Deck::standard(), 300.);
+        let seed = Seed::Numeric(11);
+        let greedy = play_rounds(&setup, &Greedy, 200, &seed, 2);
+        assert_eq!(greedy, play_rounds(&setup, &Greedy, 200, &seed, 3));
+
+        let greedy = Summary::new(&greedy);
+        let random = Summary::new(&play_rounds(&setup, &Random, 200, &seed, 2));
+        assert_eq!(greedy.mean_discards, 0.);
+        assert!(
+            greedy.win_rate() > random.win_rate(),
+            "{greedy:?} {random:?}"
+        );
+    }
+}
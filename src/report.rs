@@ -0,0 +1,233 @@
+//! Simulation results as JSON or CSV for notebooks and dashboards.
+//!
+//! Both formats carry the run's parameters alongside one row per hand type.
+//! In CSV the parameters are repeated on every row so each row stands alone.
+//! Fields an estimator doesn't produce (counts and intervals for the variance
+//! reduced estimators, standard errors for plain sampling) are null in JSON and
+//! empty in CSV. JSON needs the `serde` feature.
+
+use std::fmt::Write;
+
+use crate::{
+    seed::Seed,
+    simulation::{SimConfig, Tally},
+    stats::{Interval, IntervalMethods},
+    valid_hands::HandTypes,
+    variance::Estimate,
+};
+
+const CSV_HEADER: &str = "seed,estimator,samples,max_samples,hand_size,deck_size,confidence,\
+interval_method,target_width,hand_type,count,frequency,lower,upper,std_error";
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Row {
+    #[cfg_attr(feature = "serde", serde(serialize_with = "hand_type_name"))]
+    pub hand_type: HandTypes,
+    pub count: Option<usize>,
+    pub frequency: f64,
+    #[cfg_attr(feature = "serde", serde(flatten, serialize_with = "interval_bounds"))]
+    pub interval: Option<Interval>,
+    pub std_error: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Report {
+    pub seed: Seed,
+    pub estimator: &'static str,
+    pub samples: usize,
+    pub max_samples: usize,
+    pub hand_size: usize,
+    pub deck_size: usize,
+    pub confidence: f64,
+    pub interval_method: IntervalMethods,
+    pub target_width: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(rename = "results"))]
+    pub rows: Vec<Row>,
+}
+
+impl Report {
+    fn new(config: &SimConfig, estimator: &'static str, samples: usize, rows: Vec<Row>) -> Report {
+        Report {
+            seed: config.seed.clone(),
+            estimator,
+            samples,
+            max_samples: config.samples,
+            hand_size: config.hand_size,
+            deck_size: config.deck.cards.len(),
+            confidence: config.confidence,
+            interval_method: config.interval_method,
+            target_width: config.target_width,
+            rows,
+        }
+    }
+
+    pub fn from_tally(tally: &Tally, config: &SimConfig) -> Report {
+        let rows = HandTypes::ALL
+            .iter()
+            .map(|hand_type| Row {
+                hand_type: *hand_type,
+                count: Some(tally.count(*hand_type)),
+                frequency: tally.frequency(*hand_type),
+                interval: Some(tally.interval(
+                    *hand_type,
+                    config.interval_method,
+                    config.confidence,
+                )),
+                std_error: None,
+            })
+            .collect();
+        Report::new(config, "plain", tally.samples, rows)
+    }
+
+    pub fn from_estimates(
+        estimates: &[(HandTypes, Estimate)],
+        config: &SimConfig,
+        estimator: &'static str,
+    ) -> Report {
+        let rows = estimates
+            .iter()
+            .map(|(hand_type, estimate)| Row {
+                hand_type: *hand_type,
+                count: None,
+                frequency: estimate.mean,
+                interval: None,
+                std_error: Some(estimate.std_error),
+            })
+            .collect();
+        let samples = estimates.first().map_or(0, |(_, e)| e.samples);
+        Report::new(config, estimator, samples, rows)
+    }
+
+    /// Pretty printed, with the rows under "results".
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("reports have no map keys to fail on")
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{CSV_HEADER}\n");
+        let optional = |value: Option<f64>| value.map_or(String::new(), |v| v.to_string());
+        for row in &self.rows {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                csv_field(&self.seed.to_string()),
+                self.estimator,
+                self.samples,
+                self.max_samples,
+                self.hand_size,
+                self.deck_size,
+                self.confidence,
+                self.interval_method.to_str(),
+                optional(self.target_width),
+                csv_field(row.hand_type.to_str()),
+                row.count.map_or(String::new(), |c| c.to_string()),
+                row.frequency,
+                optional(row.interval.map(|i| i.lower)),
+                optional(row.interval.map(|i| i.upper)),
+                optional(row.std_error),
+            );
+        }
+        csv
+    }
+}
+
+// As in the CSV, e.g. "Two Pair" rather than the variant name.
+#[cfg(feature = "serde")]
+fn hand_type_name<S: serde::Serializer>(
+    hand_type: &HandTypes,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(hand_type.to_str())
+}
+
+// The interval's bounds as two fields of the row, null without an interval.
+#[cfg(feature = "serde")]
+fn interval_bounds<S: serde::Serializer>(
+    interval: &Option<Interval>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[derive(serde::Serialize)]
+    struct Bounds {
+        lower: Option<f64>,
+        upper: Option<f64>,
+    }
+
+    serde::Serialize::serialize(
+        &Bounds {
+            lower: interval.map(|i| i.lower),
+            upper: interval.map(|i| i.upper),
+        },
+        serializer,
+    )
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{seed::Seed, simulation::SimConfig, valid_hands::HandTypes, variance::stratified};
+
+    use super::{Report, CSV_HEADER};
+
+    fn config() -> SimConfig {
+        let mut config = SimConfig::new(2000, 8);
+        config.seed = Seed::Balatro("7LB2WVPK".to_string());
+        config
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_carries_parameters_and_results() {
+        use crate::simulation::simulate;
+
+        let config = config();
+        let tally = simulate(&config);
+        let json: serde_json::Value =
+            serde_json::from_str(&Report::from_tally(&tally, &config).to_json()).unwrap();
+
+        assert_eq!(json["seed"], "7LB2WVPK");
+        assert_eq!(json["samples"], 2000);
+        assert_eq!(json["hand_size"], 8);
+        assert_eq!(json["deck_size"], 52);
+        assert_eq!(json["interval_method"], "wilson");
+        assert!(json["target_width"].is_null());
+
+        let results = json["results"].as_array().unwrap();
+        assert_eq!(results.len(), HandTypes::ALL.len());
+        let pair = &results[HandTypes::Pair as usize];
+        assert_eq!(pair["hand_type"], "Pair");
+        assert_eq!(pair["count"], tally.count(HandTypes::Pair));
+        assert_eq!(pair["frequency"], tally.frequency(HandTypes::Pair));
+        assert!(pair["lower"].as_f64().unwrap() < pair["upper"].as_f64().unwrap());
+        assert!(pair["std_error"].is_null());
+    }
+
+    #[test]
+    fn csv_has_a_row_per_hand_type() {
+        let config = config();
+        let report = Report::from_estimates(&stratified(&config), &config, "stratified");
+        let csv = report.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines.len(), HandTypes::ALL.len() + 1);
+        let columns = CSV_HEADER.split(',').count();
+        for line in &lines[1..] {
+            assert_eq!(line.split(',').count(), columns, "{line}");
+            assert!(line.starts_with("7LB2WVPK,stratified,"));
+        }
+        // Stratified estimates have a standard error but no count or interval.
+        let fields: Vec<&str> = lines[1].split(',').collect();
+        assert_eq!(fields[10], "");
+        assert!(fields[14].parse::<f64>().is_ok());
+    }
+}
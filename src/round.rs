@@ -0,0 +1,464 @@
+//! Whole rounds against a blind: draw a hand, then play or discard until the
+//! blind's chips are reached or the hands run out. A [`Policy`] makes every
+//! decision.
+//!
+//! The deck is shuffled once when the round is dealt and everything after that
+//! is decided by the actions taken, so the same seed deals the same cards to
+//! every policy and their results can be compared round by round.
+
+use std::{error::Error, fmt};
+
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    balatro_rng::BalatroRng,
+    card::Card,
+    deck::Deck,
+    history::Event,
+    joker::Joker,
+    policy::Policy,
+    score::{score_with, HandLevels, Score, MAX_PLAYED},
+    seed::Seed,
+    simulation::run_indexed,
+    solve::MAX_DISCARD,
+};
+
+/// Everything needed to deal a round.
+#[derive(Debug, Clone)]
+pub struct RoundSetup {
+    /// The draw pile, shuffled when the round is dealt.
+    pub deck: Deck,
+    /// Cards already held before the first draw.
+    pub held: Vec<Card>,
+    pub hand_size: usize,
+    pub hands: u32,
+    pub discards: u32,
+    /// Chips needed to win.
+    pub blind: f64,
+    pub jokers: Vec<Joker>,
+    pub levels: HandLevels,
+}
+
+impl RoundSetup {
+    /// A round from a full deck with the game's defaults: 8 cards, 4 hands, 3
+    /// discards, no Jokers and every hand at level one.
+    pub fn new(deck: Deck, blind: f64) -> RoundSetup {
+        RoundSetup {
+            deck,
+            held: vec![],
+            hand_size: 8,
+            hands: 4,
+            discards: 3,
+            blind,
+            jokers: vec![],
+            levels: HandLevels::default(),
+        }
+    }
+
+    pub fn deal(&self, rng: &mut ChaCha8Rng) -> Round {
+        let mut deck = self.deck.cards.clone();
+        deck.shuffle(rng);
+        self.round_from(deck)
+    }
+
+    /// Deals the way the game does on a Balatro seed: every blind of an ante
+    /// reshuffles the deck from the "nr" stream for that ante, so the first call
+    /// for an ante is its Small Blind, the next its Big Blind and so on.
+    pub fn deal_balatro(&self, rng: &mut BalatroRng, ante: u32) -> Round {
+        let mut deck = self.deck.clone();
+        rng.shuffle_deck(&mut deck, ante);
+        self.round_from(deck.cards)
+    }
+
+    // A round drawing from `deck`, already shuffled, top card last.
+    fn round_from(&self, deck: Vec<Card>) -> Round {
+        let mut round = Round {
+            deck,
+            hand: self.held.clone(),
+            hand_size: self.hand_size,
+            hands_left: self.hands,
+            discards_left: self.discards,
+            chips: 0.,
+            blind: self.blind,
+            jokers: self.jokers.clone(),
+            levels: self.levels,
+        };
+        round.draw();
+        round
+    }
+}
+
+/// Cards are picked by their position in the held hand.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Action {
+    Play(Vec<usize>),
+    Discard(Vec<usize>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionError {
+    RoundOver,
+    NoCards,
+    TooManyCards,
+    /// A position past the end of the hand, or the same one twice.
+    BadCard,
+    NoDiscardsLeft,
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            ActionError::RoundOver => "the round is over",
+            ActionError::NoCards => "no cards picked",
+            ActionError::TooManyCards => "more than five cards picked",
+            ActionError::BadCard => "picked a card that isn't held",
+            ActionError::NoDiscardsLeft => "no discards left",
+        };
+        f.write_str(message)
+    }
+}
+
+impl Error for ActionError {}
+
+/// What a policy gets to see. The draw pile is sorted so its order isn't
+/// given away.
+#[derive(Debug, Clone)]
+pub struct View<'a> {
+    pub hand: &'a [Card],
+    pub remaining: Vec<Card>,
+    pub hand_size: usize,
+    pub hands_left: u32,
+    pub discards_left: u32,
+    pub chips: f64,
+    pub blind: f64,
+    pub jokers: &'a [Joker],
+    pub levels: &'a HandLevels,
+}
+
+impl View<'_> {
+    /// A round in the state this view shows, with the unseen cards in a random
+    /// order. Searching policies play these out to guess at what's to come.
+    pub fn sample_round(&self, rng: &mut ChaCha8Rng) -> Round {
+        let mut deck = self.remaining.clone();
+        deck.shuffle(rng);
+        Round {
+            deck,
+            hand: self.hand.to_vec(),
+            hand_size: self.hand_size,
+            hands_left: self.hands_left,
+            discards_left: self.discards_left,
+            chips: self.chips,
+            blind: self.blind,
+            jokers: self.jokers.to_vec(),
+            levels: *self.levels,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Round {
+    // Dealt from the back, like Deck.
+    deck: Vec<Card>,
+    hand: Vec<Card>,
+    hand_size: usize,
+    hands_left: u32,
+    discards_left: u32,
+    chips: f64,
+    blind: f64,
+    jokers: Vec<Joker>,
+    levels: HandLevels,
+}
+
+impl Round {
+    pub fn view(&self) -> View<'_> {
+        let mut remaining = self.deck.clone();
+        remaining.sort();
+        View {
+            hand: &self.hand,
+            remaining,
+            hand_size: self.hand_size,
+            hands_left: self.hands_left,
+            discards_left: self.discards_left,
+            chips: self.chips,
+            blind: self.blind,
+            jokers: &self.jokers,
+            levels: &self.levels,
+        }
+    }
+
+    pub fn hand(&self) -> &[Card] {
+        &self.hand
+    }
+
+    pub fn chips(&self) -> f64 {
+        self.chips
+    }
+
+    pub fn won(&self) -> bool {
+        self.chips >= self.blind
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.won() || self.hands_left == 0 || self.hand.is_empty()
+    }
+
+    fn draw(&mut self) {
+        while self.hand.len() < self.hand_size {
+            match self.deck.pop() {
+                Some(card) => self.hand.push(card),
+                None => break,
+            }
+        }
+    }
+
+    // Takes the picked cards out of the hand, in the order they were picked.
+    fn take(&mut self, picked: &[usize]) -> Result<Vec<Card>, ActionError> {
+        if picked.is_empty() {
+            return Err(ActionError::NoCards);
+        }
+        if picked.len() > MAX_PLAYED.max(MAX_DISCARD) {
+            return Err(ActionError::TooManyCards);
+        }
+        let mut sorted = picked.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if sorted.len() != picked.len() || sorted.last() >= Some(&self.hand.len()) {
+            return Err(ActionError::BadCard);
+        }
+
+        let cards = picked.iter().map(|i| self.hand[*i]).collect();
+        for i in sorted.into_iter().rev() {
+            self.hand.remove(i);
+        }
+        Ok(cards)
+    }
+
+    /// Plays or discards the picked cards and draws back up to the hand size.
+    /// A play returns its score.
+    pub fn apply(&mut self, action: &Action) -> Result<Option<Score>, ActionError> {
+        if self.is_over() {
+            return Err(ActionError::RoundOver);
+        }
+        let score = match action {
+            Action::Play(picked) => {
+                let played = self.take(picked)?;
+                let score = score_with(&played, &self.jokers, &self.levels);
+                self.chips += score.total();
+                self.hands_left -= 1;
+                Some(score)
+            }
+            Action::Discard(picked) => {
+                if self.discards_left == 0 {
+                    return Err(ActionError::NoDiscardsLeft);
+                }
+                self.take(picked)?;
+                self.discards_left -= 1;
+                None
+            }
+        };
+        self.draw();
+        Ok(score)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundResult {
+    pub won: bool,
+    pub chips: f64,
+    pub hands_played: u32,
+    pub discards_used: u32,
+}
+
+/// Plays the round out with the policy. An action the round rejects ends it
+/// as a loss, since the policy would ask for the same thing again.
+pub fn play_round(
+    setup: &RoundSetup,
+    policy: &mut dyn Policy,
+    rng: &mut ChaCha8Rng,
+) -> RoundResult {
+    play_round_logged(setup, policy, rng, &mut |_| {})
+}
+
+/// [`play_round`], passing everything that happens to `log` as it happens.
+pub fn play_round_logged(
+    setup: &RoundSetup,
+    policy: &mut dyn Policy,
+    rng: &mut ChaCha8Rng,
+    log: &mut dyn FnMut(Event),
+) -> RoundResult {
+    let mut round = setup.deal(rng);
+    log(Event::Deal(round.hand.clone()));
+    while !round.is_over() {
+        let action = policy.choose(&round.view(), rng);
+        let (Action::Play(picked) | Action::Discard(picked)) = &action;
+        let cards: Vec<Card> = picked
+            .iter()
+            .filter_map(|i| round.hand.get(*i))
+            .copied()
+            .collect();
+        let kept = round.hand.len().saturating_sub(picked.len());
+        match round.apply(&action) {
+            Ok(Some(score)) => log(Event::Play {
+                picked: picked.clone(),
+                cards,
+                hand_type: score.hand_type,
+                chips: score.chips,
+                mult: score.mult,
+            }),
+            Ok(None) => log(Event::Discard {
+                picked: picked.clone(),
+                cards,
+            }),
+            Err(_) => {
+                log(Event::Rejected(action));
+                break;
+            }
+        }
+        if round.hand.len() > kept {
+            log(Event::Draw(round.hand[kept..].to_vec()));
+        }
+    }
+    log(Event::End {
+        won: round.won(),
+        chips: round.chips,
+    });
+    RoundResult {
+        won: round.won(),
+        chips: round.chips,
+        hands_played: setup.hands - round.hands_left,
+        discards_used: setup.discards - round.discards_left,
+    }
+}
+
+/// Plays `rounds` rounds with a fresh copy of the policy each, in parallel.
+/// Round i is dealt the same cards for every policy given the same seed.
+pub fn play_rounds<P: Policy + Clone + Sync>(
+    setup: &RoundSetup,
+    policy: &P,
+    rounds: usize,
+    seed: &Seed,
+    threads: usize,
+) -> Vec<RoundResult> {
+    run_indexed(0..rounds, threads, seed.to_u64(), &|rng, _| {
+        play_round(setup, &mut policy.clone(), rng)
+    })
+}
+
+/// Win rate and averages over a set of rounds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub rounds: usize,
+    pub wins: usize,
+    pub mean_chips: f64,
+    pub mean_hands: f64,
+    pub mean_discards: f64,
+}
+
+impl Summary {
+    pub fn new(results: &[RoundResult]) -> Summary {
+        let n = results.len().max(1) as f64;
+        let mean = |f: fn(&RoundResult) -> f64| results.iter().map(f).sum::<f64>() / n;
+        Summary {
+            rounds: results.len(),
+            wins: results.iter().filter(|r| r.won).count(),
+            mean_chips: mean(|r| r.chips),
+            mean_hands: mean(|r| f64::from(r.hands_played)),
+            mean_discards: mean(|r| f64::from(r.discards_used)),
+        }
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.rounds.max(1) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{balatro_rng::BalatroRng, card::Card, deck::Deck};
+
+    use super::{Action, ActionError, RoundSetup};
+
+    #[test]
+    fn plays_and_discards_refill_the_hand() {
+        let setup = RoundSetup::new(Deck::standard(), 1e9);
+        let mut round = setup.deal(&mut ChaCha8Rng::seed_from_u64(3));
+        assert_eq!(round.hand().len(), 8);
+        assert_eq!(round.view().remaining.len(), 44);
+
+        let before = round.hand().to_vec();
+        assert_eq!(round.apply(&Action::Discard(vec![0, 7])), Ok(None));
+        assert_eq!(round.hand()[..6], before[1..7]);
+        assert_eq!(round.view().discards_left, 2);
+
+        let played = round.hand()[0];
+        let score = round.apply(&Action::Play(vec![0])).unwrap().unwrap();
+        assert_eq!(score.scoring, [played]);
+        assert_eq!(round.chips(), score.total());
+        assert_eq!(round.view().hands_left, 3);
+        assert_eq!(round.view().remaining.len(), 41);
+
+        for bad in [vec![], vec![1, 1], vec![8], vec![0, 1, 2, 3, 4, 5]] {
+            assert!(round.apply(&Action::Play(bad)).is_err());
+        }
+        for _ in 0..2 {
+            round.apply(&Action::Discard(vec![0])).unwrap();
+        }
+        assert_eq!(
+            round.apply(&Action::Discard(vec![0])),
+            Err(ActionError::NoDiscardsLeft)
+        );
+    }
+
+    #[test]
+    fn deals_a_balatro_seed_like_the_game() {
+        // The first ante two shuffle of 7LB2WVPK is the LuaJIT checked
+        // permutation in balatro_rng's tests. Applied to the game's creation
+        // order of the deck, its last eight cards are the opening hand.
+        let setup = RoundSetup::new(Deck::standard(), 300.);
+        let mut rng = BalatroRng::new("7LB2WVPK");
+        let round = setup.deal_balatro(&mut rng, 2);
+        let mut hand = round.hand().to_vec();
+        hand.sort();
+        let mut expected: Vec<Card> = ["9S", "4H", "4C", "QD", "8C", "6D", "JH", "QH"]
+            .map(|c| c.parse().unwrap())
+            .to_vec();
+        expected.sort();
+        assert_eq!(hand, expected);
+        assert_eq!(round.hand()[0], "QH".parse().unwrap());
+
+        // The Big Blind reshuffles from the same stream, and other antes have
+        // streams of their own.
+        let big = setup.deal_balatro(&mut rng, 2);
+        assert_ne!(big.hand(), round.hand());
+        let first = setup.deal_balatro(&mut rng, 1);
+        assert_eq!(
+            first.hand(),
+            setup
+                .deal_balatro(&mut BalatroRng::new("7LB2WVPK"), 1)
+                .hand()
+        );
+    }
+
+    #[test]
+    fn ends_when_the_blind_is_beaten_or_hands_run_out() {
+        let mut setup = RoundSetup::new(Deck::standard(), 1.);
+        setup.held = vec!["AS".parse().unwrap()];
+        let mut round = setup.deal(&mut ChaCha8Rng::seed_from_u64(1));
+        round.apply(&Action::Play(vec![0])).unwrap();
+        assert!(round.won() && round.is_over());
+        assert_eq!(
+            round.apply(&Action::Play(vec![0])),
+            Err(ActionError::RoundOver)
+        );
+
+        setup.blind = 1e9;
+        setup.hands = 1;
+        let mut round = setup.deal(&mut ChaCha8Rng::seed_from_u64(1));
+        round.apply(&Action::Play(vec![0])).unwrap();
+        assert!(!round.won() && round.is_over());
+    }
+}
@@ -0,0 +1,122 @@
+//! Runs of rounds against the rising blinds of a Balatro run: a Small, Big
+//! and Boss Blind in each of eight antes, lost at the first blind not beaten.
+//!
+//! There's no shop between rounds, so the deck, Jokers and hand levels stay
+//! as the setup has them and each round is dealt from a fresh shuffle. Boss
+//! Blinds only ask for more chips; their effects aren't modelled.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    history::Event,
+    policy::Policy,
+    round::{play_round_logged, RoundSetup},
+    seed::Seed,
+    simulation::run_indexed,
+};
+
+/// Base chips of the Small Blind in antes 1 to 8 on White Stake.
+pub const ANTE_CHIPS: [f64; 8] = [300., 800., 2000., 5000., 11000., 20000., 35000., 50000.];
+/// Small, Big and Boss Blinds ask for these multiples of the base chips.
+pub const BLIND_MULTIPLIERS: [f64; 3] = [1., 1.5, 2.];
+pub const BLINDS: u32 = (ANTE_CHIPS.len() * BLIND_MULTIPLIERS.len()) as u32;
+
+/// Chips needed to beat blind `blind` (0 to 2) of `ante` (1 to 8).
+pub fn blind_chips(ante: u32, blind: usize) -> f64 {
+    ANTE_CHIPS[ante as usize - 1] * BLIND_MULTIPLIERS[blind]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunResult {
+    /// The ante of the blind that ended the run, or 8 for a won run.
+    pub ante: u32,
+    pub blinds_beaten: u32,
+    /// Chips scored over the whole run.
+    pub chips: f64,
+}
+
+impl RunResult {
+    pub fn won(&self) -> bool {
+        self.blinds_beaten == BLINDS
+    }
+}
+
+/// Plays blinds in order with a fresh copy of the setup's deck until one isn't
+/// beaten. The setup's own blind is ignored.
+pub fn play_run(setup: &RoundSetup, policy: &mut dyn Policy, rng: &mut ChaCha8Rng) -> RunResult {
+    play_run_logged(setup, policy, rng, &mut |_| {})
+}
+
+/// [`play_run`], passing everything that happens to `log` as it happens.
+pub fn play_run_logged(
+    setup: &RoundSetup,
+    policy: &mut dyn Policy,
+    rng: &mut ChaCha8Rng,
+    log: &mut dyn FnMut(Event),
+) -> RunResult {
+    let mut setup = setup.clone();
+    let mut result = RunResult {
+        ante: 1,
+        blinds_beaten: 0,
+        chips: 0.,
+    };
+    for ante in 1..=ANTE_CHIPS.len() as u32 {
+        result.ante = ante;
+        for blind in 0..BLIND_MULTIPLIERS.len() {
+            setup.blind = blind_chips(ante, blind);
+            log(Event::Blind {
+                ante,
+                chips: setup.blind,
+            });
+            // Each round gets its own stream, so the cards dealt don't depend
+            // on how much randomness the policy used in earlier rounds.
+            let mut round_rng = ChaCha8Rng::seed_from_u64(rng.gen());
+            let round = play_round_logged(&setup, policy, &mut round_rng, log);
+            result.chips += round.chips;
+            if !round.won {
+                return result;
+            }
+            result.blinds_beaten += 1;
+        }
+    }
+    result
+}
+
+/// Plays `runs` runs in parallel, each with a fresh copy of the policy. Run i
+/// deals the same cards for each blind to every policy given the same seed.
+pub fn play_runs<P: Policy + Clone + Sync>(
+    setup: &RoundSetup,
+    policy: &P,
+    runs: usize,
+    seed: &Seed,
+    threads: usize,
+) -> Vec<RunResult> {
+    run_indexed(0..runs, threads, seed.to_u64(), &|rng, _| {
+        play_run(setup, &mut policy.clone(), rng)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{deck::Deck, policy::ChaseFlush, round::RoundSetup};
+
+    use super::{blind_chips, play_run, BLINDS};
+
+    #[test]
+    fn climbs_until_a_blind_is_lost() {
+        assert_eq!(blind_chips(1, 0), 300.);
+        assert_eq!(blind_chips(2, 2), 1600.);
+
+        let setup = RoundSetup::new(Deck::standard(), 0.);
+        let result = play_run(&setup, &mut ChaseFlush, &mut ChaCha8Rng::seed_from_u64(2));
+        // Without Jokers or levels four Straight Flushes score under 5000, so
+        // no run gets past ante 4.
+        assert!(result.ante <= 4, "{result:?}");
+        assert!(!result.won() && result.blinds_beaten < BLINDS);
+        assert_eq!(result.blinds_beaten / 3 + 1, result.ante);
+    }
+}
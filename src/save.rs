@@ -0,0 +1,333 @@
+//! Imports the run in progress from Balatro's save file, `save.jkr` in the
+//! profile folder (e.g. `%AppData%/Balatro/1/save.jkr` on Windows). The file is
+//! a raw deflate stream of a Lua table literal, see [`crate::lua`].
+//!
+//! Only what the rest of the crate models is read: the draw pile, the held
+//! cards, the scoring Jokers, hand levels, money, hands and discards left and
+//! the current blind. Hand types this crate doesn't know, like Five of a Kind,
+//! are skipped.
+
+use std::{error::Error, fmt, fs, io::Read, path::Path};
+
+use flate2::read::DeflateDecoder;
+
+use crate::{
+    card::Card,
+    deck::Deck,
+    hand::Hand,
+    joker::Joker,
+    lua::{self, LuaTable, LuaValue, ParseLuaError},
+    modifiers::{Editions, Enhancements, Seals},
+    score::HandLevels,
+    seed::Seed,
+    suit::Suits,
+    valid_hands::HandTypes,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Blind {
+    pub name: String,
+    /// Chips needed to beat it.
+    pub chips: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Save {
+    /// The cards still to be drawn, dealt from the back like the game.
+    pub deck: Deck,
+    /// The held cards, with `size` set to the hand size.
+    pub hand: Hand,
+    pub jokers: Vec<Joker>,
+    /// Names of held Jokers that [`crate::joker`] doesn't model.
+    pub other_jokers: Vec<String>,
+    pub levels: HandLevels,
+    pub money: f64,
+    pub hands: u32,
+    pub discards: u32,
+    /// None between rounds.
+    pub blind: Option<Blind>,
+    pub seed: Option<Seed>,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    /// The file isn't a deflate stream.
+    Decompress(std::io::Error),
+    Lua(ParseLuaError),
+    /// The table is missing a field every save has, or it has the wrong type.
+    Missing(String),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "couldn't read save: {e}"),
+            SaveError::Decompress(e) => write!(f, "couldn't decompress save: {e}"),
+            SaveError::Lua(e) => write!(f, "couldn't parse save: {e}"),
+            SaveError::Missing(field) => write!(f, "save has no {field}"),
+        }
+    }
+}
+
+impl Error for SaveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SaveError::Io(e) | SaveError::Decompress(e) => Some(e),
+            SaveError::Lua(e) => Some(e),
+            SaveError::Missing(_) => None,
+        }
+    }
+}
+
+/// Inflates a save file into its Lua source. Files that are already plain
+/// text, e.g. a save decompressed by hand for editing, are passed through.
+pub fn decompress(bytes: &[u8]) -> Result<String, SaveError> {
+    if bytes.trim_ascii_start().starts_with(b"return") {
+        return Ok(String::from_utf8_lossy(bytes).into_owned());
+    }
+    let mut source = vec![];
+    DeflateDecoder::new(bytes)
+        .read_to_end(&mut source)
+        .map_err(SaveError::Decompress)?;
+    Ok(String::from_utf8_lossy(&source).into_owned())
+}
+
+// The names the game uses in GAME.hands.
+fn game_name(hand_type: HandTypes) -> &'static str {
+    match hand_type {
+        HandTypes::ThreeOAK => "Three of a Kind",
+        HandTypes::FourOAK => "Four of a Kind",
+        _ => hand_type.to_str(),
+    }
+}
+
+fn missing(field: &str) -> SaveError {
+    SaveError::Missing(field.to_string())
+}
+
+fn table<'a>(parent: &'a LuaTable, key: &str) -> Result<&'a LuaTable, SaveError> {
+    parent.table(key).ok_or_else(|| missing(key))
+}
+
+fn count(parent: &LuaTable, key: &str) -> Result<u32, SaveError> {
+    let n = parent.number(key).ok_or_else(|| missing(key))?;
+    Ok(n.max(0.) as u32)
+}
+
+// A playing card as Card:save() writes it: the suit and rank under `base`,
+// the enhancement as the center key in `save_fields`, then edition and seal.
+fn card(saved: &LuaTable) -> Result<Card, SaveError> {
+    let base = table(saved, "base")?;
+    let suit = match base.str("suit") {
+        Some("Spades") => Suits::Spades,
+        Some("Hearts") => Suits::Hearts,
+        Some("Clubs") => Suits::Clubs,
+        Some("Diamonds") => Suits::Diamonds,
+        _ => return Err(missing("card suit")),
+    };
+    let value = match base.str("value") {
+        Some("Ace") => 14,
+        Some("King") => 13,
+        Some("Queen") => 12,
+        Some("Jack") => 11,
+        Some(number) => number
+            .parse()
+            .ok()
+            .filter(|v| (2..=10).contains(v))
+            .ok_or_else(|| missing("card rank"))?,
+        None => return Err(missing("card rank")),
+    };
+
+    let mut card = Card::from_card_value(value, Some(suit));
+    let center = saved.table("save_fields").and_then(|f| f.str("center"));
+    card.enhancement = center
+        .and_then(|c| c.strip_prefix("m_"))
+        .and_then(|name| Enhancements::ALL.into_iter().find(|e| e.to_str() == name));
+    card.edition = edition(saved);
+    card.seal = saved
+        .str("seal")
+        .and_then(|seal| Seals::ALL.into_iter().find(|s| format!("{s:?}") == seal));
+    Ok(card)
+}
+
+// Editions are saved as a table with a `type` such as "holo", or in older
+// saves only a flag like `polychrome = true`. Negative has no effect on
+// scoring and is dropped.
+fn edition(saved: &LuaTable) -> Option<Editions> {
+    let edition = saved.table("edition")?;
+    if let Some(name) = edition.str("type") {
+        return Editions::from_name(name);
+    }
+    Editions::ALL.into_iter().find(|e| {
+        let flag = format!("{e:?}").to_ascii_lowercase();
+        let flag = if *e == Editions::Holographic {
+            "holo"
+        } else {
+            &flag
+        };
+        edition.get(flag).and_then(LuaValue::as_bool) == Some(true)
+    })
+}
+
+fn cards(area: &LuaTable) -> Result<Vec<Card>, SaveError> {
+    table(area, "cards")?
+        .array()
+        .into_iter()
+        .map(|saved| card(saved.as_table().ok_or_else(|| missing("card"))?))
+        .collect()
+}
+
+impl Save {
+    pub fn load(path: &Path) -> Result<Save, SaveError> {
+        let bytes = fs::read(path).map_err(SaveError::Io)?;
+        Save::from_bytes(&bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Save, SaveError> {
+        Save::from_lua(&decompress(bytes)?)
+    }
+
+    pub fn from_lua(source: &str) -> Result<Save, SaveError> {
+        let root = lua::parse(source).map_err(SaveError::Lua)?;
+        let root = root.as_table().ok_or_else(|| missing("table"))?;
+        let areas = table(root, "cardAreas")?;
+        let game = table(root, "GAME")?;
+
+        let deck = Deck {
+            cards: cards(table(areas, "deck")?)?,
+        };
+        let hand_area = table(areas, "hand")?;
+        let held = cards(hand_area)?;
+        let size = table(hand_area, "config")?
+            .number("card_limit")
+            .map_or(held.len(), |limit| limit.max(0.) as usize);
+
+        let mut jokers = vec![];
+        let mut other_jokers = vec![];
+        if let Some(area) = areas.table("jokers") {
+            for saved in table(area, "cards")?.array() {
+                let saved = saved.as_table().ok_or_else(|| missing("joker"))?;
+                let name = saved
+                    .table("ability")
+                    .and_then(|a| a.str("name"))
+                    .ok_or_else(|| missing("joker name"))?;
+                match name.parse::<Joker>() {
+                    Ok(mut joker) => {
+                        joker.edition = edition(saved);
+                        jokers.push(joker);
+                    }
+                    Err(_) => other_jokers.push(name.to_string()),
+                }
+            }
+        }
+
+        let mut levels = HandLevels::default();
+        let hands = table(game, "hands")?;
+        for hand_type in HandTypes::ALL {
+            if let Some(level) = hands
+                .table(game_name(hand_type))
+                .and_then(|h| h.number("level"))
+            {
+                levels.set(hand_type, level.max(1.) as u32);
+            }
+        }
+
+        let round = table(game, "current_round")?;
+        let blind = root.table("BLIND").and_then(|blind| {
+            let chips = blind.number("chips").filter(|c| *c > 0.)?;
+            let name = blind.str("name").unwrap_or_default().to_string();
+            Some(Blind { name, chips })
+        });
+        let seed = game
+            .table("pseudorandom")
+            .and_then(|p| p.str("seed"))
+            .and_then(|s| s.parse().ok());
+
+        Ok(Save {
+            deck,
+            hand: Hand { cards: held, size },
+            jokers,
+            other_jokers,
+            levels,
+            money: game.number("dollars").ok_or_else(|| missing("dollars"))?,
+            hands: count(round, "hands_left")?,
+            discards: count(round, "discards_left")?,
+            blind,
+            seed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::{
+        joker::{Joker, Jokers},
+        modifiers::Editions,
+        seed::Seed,
+        valid_hands::HandTypes,
+    };
+
+    use super::{decompress, Blind, Save};
+
+    // save.lua is a cut down save laid out the way the game writes it, and
+    // save.jkr is the same text raw deflated, as zlib does with wbits=-15.
+    fn fixture(name: &str) -> PathBuf {
+        [env!("CARGO_MANIFEST_DIR"), "tests", "fixtures", name]
+            .iter()
+            .collect()
+    }
+
+    #[test]
+    fn decompresses_the_sample_save() {
+        let compressed = fs::read(fixture("save.jkr")).unwrap();
+        let source = fs::read_to_string(fixture("save.lua")).unwrap();
+        assert_eq!(decompress(&compressed).unwrap(), source);
+        assert_eq!(decompress(source.as_bytes()).unwrap(), source);
+        assert!(decompress(b"not a save").is_err());
+    }
+
+    #[test]
+    fn reads_the_run_state() {
+        let save = Save::load(&fixture("save.jkr")).unwrap();
+
+        assert_eq!(save.hand.size, 8);
+        assert_eq!(
+            save.hand.to_notation(),
+            "AH KH+glass+redseal 10H 7S+poly 3C 3D"
+        );
+        assert_eq!(save.deck.cards.len(), 5);
+        assert_eq!(
+            save.deck.cards.last().unwrap().to_notation(),
+            "QH+bonus+holo"
+        );
+
+        assert_eq!(
+            save.jokers,
+            [
+                Joker::new(Jokers::LustyJoker),
+                Joker {
+                    kind: Jokers::TheTribe,
+                    edition: Some(Editions::Foil)
+                }
+            ]
+        );
+        assert_eq!(save.other_jokers, ["Blueprint"]);
+
+        assert_eq!(save.levels.level(HandTypes::Flush), 3);
+        assert_eq!(save.levels.level(HandTypes::ThreeOAK), 2);
+        assert_eq!(save.levels.level(HandTypes::Pair), 1);
+        assert_eq!(save.money, 12.);
+        assert_eq!((save.hands, save.discards), (3, 1));
+        assert_eq!(
+            save.blind,
+            Some(Blind {
+                name: "The Hook".to_string(),
+                chips: 1400.
+            })
+        );
+        assert_eq!(save.seed, Some(Seed::Balatro("7LB2WVPK".to_string())));
+    }
+}
@@ -0,0 +1,425 @@
+//! Starting positions read from TOML or JSON, for simulating a situation part
+//! way through a run instead of an opening draw from a standard deck.
+//!
+//! Every field is optional. Cards and Jokers use the same notation as the
+//! command line:
+//!
+//! ```
+//! use monte::scenario::Scenario;
+//!
+//! let scenario = Scenario::from_toml(r#"
+//!     hand = ["AS", "7D+bonus+redseal"]
+//!     hand_size = 8
+//!     hands = 3
+//!     discards = 2
+//!     jokers = ["Jolly Joker", "The Duo+poly"]
+//!     blind = 1200
+//!     seed = "7LB2WVPK"
+//!
+//!     [deck]
+//!     base = "abandoned"            # standard, abandoned, checkered or empty
+//!     remove = ["2C", "3C"]         # one copy of each
+//!     modify = ["7D+bonus+redseal"] # replaces one 7D
+//!     add = ["AS+foil"]
+//!     counts = { "5H+steel" = 2 }   # exactly this many copies
+//!
+//!     [levels]
+//!     flush = 3
+//! "#).unwrap();
+//!
+//! // 40 cards, less the two removed, plus the Ace and two Steel Fives, less
+//! // the two held.
+//! assert_eq!(scenario.deck.cards.len(), 39);
+//! assert_eq!(scenario.full_deck().cards.len(), 41);
+//! ```
+//!
+//! The deck is built from `base` by applying `remove`, `modify`, `add` and then
+//! `counts`. Held cards are taken out of the deck, so `deck` is the draw pile;
+//! each must match a deck card by rank and suit.
+//!
+//! A Balatro `save.jkr` can be loaded as a scenario too, see [`crate::save`].
+
+use std::{collections::BTreeMap, error::Error, fmt, fs, io, path::Path};
+
+use serde::Deserialize;
+
+use crate::{
+    card::Card,
+    deck::Deck,
+    hand::Hand,
+    joker::Joker,
+    round::RoundSetup,
+    save::{Save, SaveError},
+    score::HandLevels,
+    seed::Seed,
+    valid_hands::HandTypes,
+};
+
+/// A position in a run: the draw pile, the held cards and what's left to play
+/// the current blind with.
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    /// The cards still to be drawn.
+    pub deck: Deck,
+    /// The held cards, with `size` set to the hand size.
+    pub hand: Hand,
+    pub hands: u32,
+    pub discards: u32,
+    pub jokers: Vec<Joker>,
+    pub levels: HandLevels,
+    /// Chips needed to beat the blind.
+    pub blind: Option<f64>,
+    pub seed: Option<Seed>,
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(io::Error),
+    /// The file isn't valid TOML or JSON, or has fields of the wrong type.
+    Parse(String),
+    /// The file parsed but describes an impossible position.
+    Invalid(String),
+    Save(SaveError),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(e) => write!(f, "couldn't read scenario: {e}"),
+            ScenarioError::Parse(e) => write!(f, "couldn't parse scenario: {e}"),
+            ScenarioError::Invalid(e) => write!(f, "invalid scenario: {e}"),
+            ScenarioError::Save(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for ScenarioError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ScenarioError::Io(e) => Some(e),
+            ScenarioError::Save(e) => e.source(),
+            _ => None,
+        }
+    }
+}
+
+// The file as written, before the deck is built and names are checked.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ScenarioFile {
+    deck: DeckFile,
+    hand: Vec<Card>,
+    hand_size: Option<usize>,
+    hands: Option<u32>,
+    discards: Option<u32>,
+    jokers: Vec<Joker>,
+    levels: BTreeMap<String, u32>,
+    blind: Option<f64>,
+    seed: Option<Seed>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct DeckFile {
+    base: Option<String>,
+    remove: Vec<Card>,
+    modify: Vec<Card>,
+    add: Vec<Card>,
+    counts: BTreeMap<Card, usize>,
+}
+
+fn invalid(message: String) -> ScenarioError {
+    ScenarioError::Invalid(message)
+}
+
+impl DeckFile {
+    fn build(self) -> Result<Deck, ScenarioError> {
+        let mut deck = match self.base.as_deref().unwrap_or("standard") {
+            "standard" => Deck::standard(),
+            "abandoned" => Deck::abandoned(),
+            "checkered" => Deck::checkered(),
+            "empty" => Deck { cards: vec![] },
+            base => return Err(invalid(format!("unknown base deck {base:?}"))),
+        };
+
+        for card in self.remove {
+            let position = deck.cards.iter().position(|c| *c == card);
+            let position = position.ok_or_else(|| {
+                invalid(format!(
+                    "can't remove {}: not in the deck",
+                    card.to_notation()
+                ))
+            })?;
+            deck.cards.remove(position);
+        }
+        for card in self.modify {
+            let position = deck
+                .cards
+                .iter()
+                .position(|c| c.rank == card.rank && c.suit == card.suit);
+            let position = position.ok_or_else(|| {
+                invalid(format!(
+                    "can't modify {}: not in the deck",
+                    card.to_notation()
+                ))
+            })?;
+            deck.cards[position] = card;
+        }
+        deck.cards.extend(self.add);
+        for (card, count) in self.counts {
+            deck.cards.retain(|c| *c != card);
+            deck.cards.extend(std::iter::repeat_n(card, count));
+        }
+
+        Ok(deck)
+    }
+}
+
+impl Scenario {
+    pub fn from_toml(s: &str) -> Result<Scenario, ScenarioError> {
+        let file = toml::from_str(s).map_err(|e| ScenarioError::Parse(e.to_string()))?;
+        Scenario::from_file(file)
+    }
+
+    pub fn from_json(s: &str) -> Result<Scenario, ScenarioError> {
+        let file = serde_json::from_str(s).map_err(|e| ScenarioError::Parse(e.to_string()))?;
+        Scenario::from_file(file)
+    }
+
+    /// Reads a scenario file, as JSON if it ends in `.json`, a Balatro save if
+    /// it ends in `.jkr` and TOML otherwise.
+    pub fn load(path: &Path) -> Result<Scenario, ScenarioError> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+        if extension.eq_ignore_ascii_case("jkr") {
+            return Save::load(path)
+                .map(Scenario::from)
+                .map_err(ScenarioError::Save);
+        }
+        let contents = fs::read_to_string(path).map_err(ScenarioError::Io)?;
+        match extension.eq_ignore_ascii_case("json") {
+            true => Scenario::from_json(&contents),
+            false => Scenario::from_toml(&contents),
+        }
+    }
+
+    fn from_file(file: ScenarioFile) -> Result<Scenario, ScenarioError> {
+        let mut deck = file.deck.build()?;
+        for card in &file.hand {
+            // An exact copy first, then any card of the same rank and suit: a
+            // held card may have picked up an enhancement, edition or seal.
+            let position = deck
+                .cards
+                .iter()
+                .position(|c| c == card)
+                .or_else(|| {
+                    deck.cards
+                        .iter()
+                        .position(|c| c.rank == card.rank && c.suit == card.suit)
+                })
+                .ok_or_else(|| invalid(format!("held card {card} isn't in the deck")))?;
+            deck.cards.remove(position);
+        }
+
+        let size = file.hand_size.unwrap_or(8);
+        if size < file.hand.len() {
+            return Err(invalid(format!(
+                "{} cards held with a hand size of {size}",
+                file.hand.len()
+            )));
+        }
+        if size > file.hand.len() + deck.cards.len() {
+            return Err(invalid(format!(
+                "hand size {size} is larger than the {} cards held and in the deck",
+                file.hand.len() + deck.cards.len()
+            )));
+        }
+
+        let mut levels = HandLevels::default();
+        for (name, level) in file.levels {
+            let hand_type: HandTypes = name.parse().map_err(|e| invalid(format!("{e}")))?;
+            if level == 0 {
+                return Err(invalid(format!("{name} is at level 0, levels start at 1")));
+            }
+            levels.set(hand_type, level);
+        }
+
+        if let Some(blind) = file.blind {
+            if blind.is_nan() || blind <= 0. {
+                return Err(invalid(format!("blind of {blind} chips")));
+            }
+        }
+
+        Ok(Scenario {
+            deck,
+            hand: Hand {
+                cards: file.hand,
+                size,
+            },
+            hands: file.hands.unwrap_or(4),
+            discards: file.discards.unwrap_or(3),
+            jokers: file.jokers,
+            levels,
+            blind: file.blind,
+            seed: file.seed,
+        })
+    }
+
+    /// The round this position is in. `blind` is used when the file doesn't
+    /// give one.
+    pub fn round_setup(&self, blind: f64) -> RoundSetup {
+        RoundSetup {
+            deck: self.deck.clone(),
+            held: self.hand.cards.clone(),
+            hand_size: self.hand.size,
+            hands: self.hands,
+            discards: self.discards,
+            blind: self.blind.unwrap_or(blind),
+            jokers: self.jokers.clone(),
+            levels: self.levels,
+        }
+    }
+
+    /// The draw pile with the held cards put back: the whole deck for the run.
+    pub fn full_deck(&self) -> Deck {
+        let mut deck = self.deck.clone();
+        deck.cards.extend(&self.hand.cards);
+        deck
+    }
+}
+
+// Money and the blind's name have nowhere to go and are dropped.
+impl From<Save> for Scenario {
+    fn from(save: Save) -> Scenario {
+        Scenario {
+            deck: save.deck,
+            hand: save.hand,
+            hands: save.hands,
+            discards: save.discards,
+            jokers: save.jokers,
+            levels: save.levels,
+            blind: save.blind.map(|blind| blind.chips),
+            seed: save.seed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        card::Card, deck::Deck, joker::Jokers, modifiers::Enhancements, seed::Seed,
+        valid_hands::HandTypes,
+    };
+
+    use super::{Scenario, ScenarioError};
+
+    fn card(notation: &str) -> Card {
+        notation.parse().unwrap()
+    }
+
+    #[test]
+    fn defaults_to_an_opening_draw() {
+        let scenario = Scenario::from_toml("").unwrap();
+        assert_eq!(scenario.deck.cards, Deck::standard().cards);
+        assert!(scenario.hand.cards.is_empty());
+        assert_eq!(scenario.hand.size, 8);
+        assert_eq!((scenario.hands, scenario.discards), (4, 3));
+        assert_eq!(scenario.levels.level(HandTypes::Pair), 1);
+        assert_eq!(scenario.blind, None);
+    }
+
+    #[test]
+    fn builds_the_deck_and_hand() {
+        let scenario = Scenario::from_json(
+            r#"{
+                "deck": {
+                    "base": "checkered",
+                    "remove": ["2S"],
+                    "modify": ["AH+glass"],
+                    "counts": {"KS": 0, "QH+steel": 3}
+                },
+                "hand": ["AS", "AH+glass"],
+                "hand_size": 7,
+                "jokers": ["Scholar"],
+                "levels": {"Two Pair": 4},
+                "blind": 800,
+                "seed": "ABC"
+            }"#,
+        )
+        .unwrap();
+
+        let cards = &scenario.deck.cards;
+        // 52 - 1 removed - 2 Kings of Spades + 3 Steel Queens, less the 2 held.
+        assert_eq!(cards.len(), 50);
+        assert_eq!(cards.iter().filter(|c| **c == card("2S")).count(), 1);
+        assert!(!cards.contains(&card("KS")));
+        assert_eq!(cards.iter().filter(|c| **c == card("QH+steel")).count(), 3);
+        let glass = cards
+            .iter()
+            .filter(|c| c.enhancement == Some(Enhancements::Glass))
+            .count();
+        assert_eq!(glass, 0);
+        assert_eq!(scenario.full_deck().cards.len(), 52);
+
+        assert_eq!(scenario.hand.size, 7);
+        assert_eq!(scenario.jokers[0].kind, Jokers::Scholar);
+        assert_eq!(scenario.levels.level(HandTypes::TwoPair), 4);
+        assert_eq!(scenario.blind, Some(800.));
+        assert_eq!(scenario.seed, Some(Seed::Balatro("ABC".to_string())));
+    }
+
+    #[test]
+    fn holds_cards_from_the_deck() {
+        // The deck's Ace of Spades is plain; holding it as Glass still takes it.
+        let scenario = Scenario::from_toml("hand = [\"AS+glass\"]").unwrap();
+        assert!(!scenario.deck.cards.contains(&card("AS")));
+        assert_eq!(scenario.full_deck().cards.len(), 52);
+
+        let missing = Scenario::from_toml("hand = [\"AS\"]\n[deck]\nremove = [\"AS\"]");
+        assert!(
+            matches!(missing, Err(ScenarioError::Invalid(_))),
+            "{missing:?}"
+        );
+    }
+
+    #[test]
+    fn rejects_impossible_positions() {
+        let invalid = [
+            "[deck]\nbase = \"ghost\"",
+            "[deck]\nbase = \"abandoned\"\nmodify = [\"KS+glass\"]",
+            "[deck]\nremove = [\"AS\", \"AS\"]",
+            "hand = [\"AS\", \"KS\"]\nhand_size = 1",
+            "[deck]\nbase = \"empty\"\nadd = [\"AS\"]",
+            "levels = { flush = 0 }",
+            "levels = { royal = 2 }",
+        ];
+        for toml in invalid {
+            assert!(
+                matches!(Scenario::from_toml(toml), Err(ScenarioError::Invalid(_))),
+                "{toml}"
+            );
+        }
+
+        for toml in ["hand = [\"ZZ\"]", "jokers = [\"Clown\"]", "hand_sise = 8"] {
+            assert!(
+                matches!(Scenario::from_toml(toml), Err(ScenarioError::Parse(_))),
+                "{toml}"
+            );
+        }
+    }
+
+    #[test]
+    fn loads_balatro_saves() {
+        let path: std::path::PathBuf =
+            [env!("CARGO_MANIFEST_DIR"), "tests", "fixtures", "save.jkr"]
+                .iter()
+                .collect();
+        let scenario = Scenario::load(&path).unwrap();
+        assert_eq!(scenario.hand.cards.len(), 6);
+        assert_eq!(scenario.full_deck().cards.len(), 11);
+        assert_eq!((scenario.hands, scenario.discards), (3, 1));
+        assert_eq!(scenario.blind, Some(1400.));
+    }
+}
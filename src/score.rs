@@ -0,0 +1,323 @@
+//! Balatro scoring for a played hand: the hand type's base chips and mult at its
+//! level, plus the chips of every card that takes part in the hand and the
+//! effects of its enhancement, edition and seal, then the held Jokers.
+//!
+//! Only effects that depend on the played cards alone are applied. Lucky cards
+//! are random and Steel and Gold cards work from the hand, not when played.
+//! Wild and Stone cards still count by their printed suit and rank when the
+//! hand type is detected.
+
+use std::collections::HashMap;
+
+use crate::{
+    card::Card,
+    card_set::CardSet,
+    hand::Hand,
+    joker::Joker,
+    lookup::best_hand,
+    modifiers::{Editions, Enhancements, Seals},
+    suit::Ranks,
+    valid_hands::HandTypes,
+};
+
+/// Most cards that can be played at once.
+pub const MAX_PLAYED: usize = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+    pub hand_type: HandTypes,
+    /// The played cards that count towards the hand, e.g. just the two cards of
+    /// a Pair.
+    pub scoring: Vec<Card>,
+    pub chips: f64,
+    pub mult: f64,
+}
+
+impl Score {
+    pub fn total(&self) -> f64 {
+        (self.chips * self.mult).floor()
+    }
+}
+
+/// Base chips and mult of each hand type at level one.
+pub fn base_score(hand_type: HandTypes) -> (f64, f64) {
+    match hand_type {
+        HandTypes::HighCard => (5., 1.),
+        HandTypes::Pair => (10., 2.),
+        HandTypes::TwoPair => (20., 2.),
+        HandTypes::ThreeOAK => (30., 3.),
+        HandTypes::Straight => (30., 4.),
+        HandTypes::Flush => (35., 4.),
+        HandTypes::FullHouse => (40., 4.),
+        HandTypes::FourOAK => (60., 7.),
+        HandTypes::StraightFlush => (100., 8.),
+    }
+}
+
+/// Chips and mult a hand type gains each time it levels up.
+pub fn level_up(hand_type: HandTypes) -> (f64, f64) {
+    match hand_type {
+        HandTypes::HighCard => (10., 1.),
+        HandTypes::Pair => (15., 1.),
+        HandTypes::TwoPair => (20., 1.),
+        HandTypes::ThreeOAK => (20., 2.),
+        HandTypes::Straight => (30., 3.),
+        HandTypes::Flush => (15., 2.),
+        HandTypes::FullHouse => (25., 2.),
+        HandTypes::FourOAK => (30., 3.),
+        HandTypes::StraightFlush => (40., 4.),
+    }
+}
+
+/// The level of every hand type, raised by Planet cards. All start at one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandLevels([u32; 9]);
+
+impl Default for HandLevels {
+    fn default() -> HandLevels {
+        HandLevels([1; 9])
+    }
+}
+
+impl HandLevels {
+    pub fn level(&self, hand_type: HandTypes) -> u32 {
+        self.0[hand_type as usize]
+    }
+
+    /// Sets a hand type's level. Levels below one are raised to one.
+    pub fn set(&mut self, hand_type: HandTypes, level: u32) {
+        self.0[hand_type as usize] = level.max(1);
+    }
+
+    /// Base chips and mult of the hand type at its current level.
+    pub fn base_score(&self, hand_type: HandTypes) -> (f64, f64) {
+        let (chips, mult) = base_score(hand_type);
+        let (extra_chips, extra_mult) = level_up(hand_type);
+        let ups = f64::from(self.level(hand_type) - 1);
+        (chips + extra_chips * ups, mult + extra_mult * ups)
+    }
+}
+
+/// Chips a card adds when it scores: its number, 10 for faces and 11 for Aces.
+/// Stone cards give a flat 50 instead.
+pub fn card_chips(card: &Card) -> f64 {
+    if card.enhancement == Some(Enhancements::Stone) {
+        return 50.;
+    }
+    match card.rank {
+        Ranks::Ace => 11.,
+        Ranks::King | Ranks::Queen | Ranks::Jack => 10.,
+        Ranks::Blank => 0.,
+        _ => card.value as f64,
+    }
+}
+
+// Picks out the cards that make up the hand type. Five card hands score every
+// card; the n-of-a-kinds only score the matching ranks, and a lone high card
+// only scores itself. Stone cards always score.
+fn scoring_cards(cards: &[Card], hand_type: HandTypes) -> Vec<Card> {
+    let mut held: HashMap<usize, usize> = HashMap::new();
+    for card in cards {
+        *held.entry(card.value).or_default() += 1;
+    }
+    let matching = |keep: &dyn Fn(usize) -> bool| -> Vec<Card> {
+        cards
+            .iter()
+            .filter(|c| keep(held[&c.value]))
+            .copied()
+            .collect()
+    };
+
+    let in_hand = match hand_type {
+        HandTypes::HighCard => cards
+            .iter()
+            .max_by_key(|c| c.value)
+            .into_iter()
+            .copied()
+            .collect(),
+        HandTypes::Pair => matching(&|count| count == 2),
+        HandTypes::TwoPair => matching(&|count| count >= 2),
+        HandTypes::ThreeOAK => matching(&|count| count == 3),
+        HandTypes::FourOAK => matching(&|count| count == 4),
+        HandTypes::Straight
+        | HandTypes::Flush
+        | HandTypes::FullHouse
+        | HandTypes::StraightFlush => cards.to_vec(),
+    };
+
+    // Keep the played order, which is the order cards are scored in.
+    let mut in_hand = in_hand.into_iter().peekable();
+    let mut scoring = vec![];
+    for card in cards {
+        if in_hand.peek() == Some(card) {
+            scoring.push(*card);
+            in_hand.next();
+        } else if card.enhancement == Some(Enhancements::Stone) {
+            scoring.push(*card);
+        }
+    }
+    scoring
+}
+
+// Adds one scoring card's chips and effects. Order matters once multipliers
+// are involved, so this follows the game: chips, enhancement, then edition.
+fn trigger(card: &Card, chips: &mut f64, mult: &mut f64) {
+    *chips += card_chips(card);
+    match card.enhancement {
+        Some(Enhancements::Bonus) => *chips += 30.,
+        Some(Enhancements::Mult) => *mult += 4.,
+        Some(Enhancements::Glass) => *mult *= 2.,
+        _ => {}
+    }
+    match card.edition {
+        Some(Editions::Foil) => *chips += 50.,
+        Some(Editions::Holographic) => *mult += 10.,
+        Some(Editions::Polychrome) => *mult *= 1.5,
+        None => {}
+    }
+}
+
+/// Scores the cards as a single played hand at level one with no Jokers.
+/// Balatro only allows up to MAX_PLAYED cards per hand, which is left to the
+/// caller to enforce.
+pub fn score(cards: &[Card]) -> Score {
+    score_with(cards, &[], &HandLevels::default())
+}
+
+/// Scores the cards as a single played hand with the given Jokers, applied
+/// left to right, and hand levels.
+pub fn score_with(cards: &[Card], jokers: &[Joker], levels: &HandLevels) -> Score {
+    // The lookup tables are much faster and agree when the cards are distinct.
+    let set = CardSet::from_cards(cards);
+    let hand_type = if set.len() == cards.len() {
+        best_hand(set)
+    } else {
+        HandTypes::best(&Hand {
+            cards: cards.to_vec(),
+            size: cards.len(),
+        })
+    };
+    let scoring = scoring_cards(cards, hand_type);
+    let (mut chips, mut mult) = levels.base_score(hand_type);
+    for card in &scoring {
+        // A Red Seal retriggers the card, along with the Jokers it triggers.
+        let triggers = if card.seal == Some(Seals::Red) { 2 } else { 1 };
+        for _ in 0..triggers {
+            trigger(card, &mut chips, &mut mult);
+            for joker in jokers {
+                joker.on_scored(card, &mut chips, &mut mult);
+            }
+        }
+    }
+    for joker in jokers {
+        joker.independent(cards, jokers.len(), &mut chips, &mut mult);
+    }
+
+    Score {
+        hand_type,
+        scoring,
+        chips,
+        mult,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{card::Card, joker::Joker, suit::Suits, valid_hands::HandTypes};
+
+    use super::{score, score_with, HandLevels};
+
+    fn cards(values: &[(usize, Suits)]) -> Vec<Card> {
+        values
+            .iter()
+            .map(|(v, s)| Card::from_card_value(*v, Some(*s)))
+            .collect()
+    }
+
+    #[test]
+    fn scores_only_the_cards_in_the_hand() {
+        let pair = score(&cards(&[
+            (13, Suits::Spades),
+            (13, Suits::Hearts),
+            (2, Suits::Clubs),
+            (7, Suits::Diamonds),
+        ]));
+        assert_eq!(pair.hand_type, HandTypes::Pair);
+        assert_eq!(pair.scoring.len(), 2);
+        // (10 + 10 + 10) x 2
+        assert_eq!(pair.total(), 60.);
+
+        let high = score(&cards(&[(14, Suits::Spades), (9, Suits::Hearts)]));
+        assert_eq!(high.hand_type, HandTypes::HighCard);
+        // (5 + 11) x 1
+        assert_eq!(high.total(), 16.);
+    }
+
+    #[test]
+    fn five_card_hands_score_every_card() {
+        let flush = score(&cards(&[
+            (2, Suits::Hearts),
+            (5, Suits::Hearts),
+            (9, Suits::Hearts),
+            (11, Suits::Hearts),
+            (14, Suits::Hearts),
+        ]));
+        assert_eq!(flush.hand_type, HandTypes::Flush);
+        // (35 + 2 + 5 + 9 + 10 + 11) x 4
+        assert_eq!(flush.total(), 288.);
+
+        let royal = score(&cards(&[10, 11, 12, 13, 14].map(|v| (v, Suits::Spades))));
+        assert_eq!(royal.hand_type, HandTypes::StraightFlush);
+        // (100 + 10 + 10 + 10 + 10 + 11) x 8
+        assert_eq!(royal.total(), 1208.);
+    }
+
+    #[test]
+    fn applies_card_modifiers() {
+        let played: Vec<Card> = ["KS+bonus", "KH+glass+foil", "2C+stone", "7D+mult"]
+            .iter()
+            .map(|c| c.parse().unwrap())
+            .collect();
+        let pair = score(&played);
+        assert_eq!(pair.hand_type, HandTypes::Pair);
+        // The unpaired Stone card scores, the 7 with its Mult doesn't.
+        assert_eq!(pair.scoring.len(), 3);
+        // Chips 10 + (10 + 30) + (10 + 50) + 50, mult 2 x 2.
+        assert_eq!(pair.chips, 160.);
+        assert_eq!(pair.mult, 4.);
+
+        let retriggered = score(&["AS+holo+redseal".parse().unwrap()]);
+        // Chips 5 + 11 + 11, mult 1 + 10 + 10.
+        assert_eq!(retriggered.total(), 27. * 21.);
+    }
+
+    #[test]
+    fn applies_levels_and_jokers() {
+        let flush = cards(&[2, 5, 9, 11, 14].map(|v| (v, Suits::Hearts)));
+        let mut levels = HandLevels::default();
+        levels.set(HandTypes::Flush, 3);
+        assert_eq!(levels.base_score(HandTypes::Flush), (65., 8.));
+        // (65 + 37) x 8
+        assert_eq!(score_with(&flush, &[], &levels).total(), 816.);
+
+        let jokers: Vec<Joker> = ["Lusty Joker", "Jolly Joker", "The Tribe+poly"]
+            .iter()
+            .map(|j| j.parse().unwrap())
+            .collect();
+        let scored = score_with(&flush, &jokers, &HandLevels::default());
+        // Chips 35 + 37; mult 4 + 3 per Heart, no Pair for Jolly, then x2 x1.5.
+        assert_eq!(scored.chips, 72.);
+        assert_eq!(scored.mult, 57.);
+
+        // Jolly sees the Pair inside a Full House.
+        let full_house = cards(&[
+            (13, Suits::Spades),
+            (13, Suits::Hearts),
+            (13, Suits::Clubs),
+            (2, Suits::Clubs),
+            (2, Suits::Spades),
+        ]);
+        let scored = score_with(&full_house, &jokers[1..2], &HandLevels::default());
+        assert_eq!(scored.mult, 12.);
+    }
+}
@@ -0,0 +1,180 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+// Balatro's own seed generator never emits '0' or 'O' to avoid confusing them.
+const BALATRO_SEED_CHARS: &[u8] = b"123456789ABCDEFGHIJKLMNPQRSTUVWXYZ";
+const BALATRO_SEED_LEN: usize = 8;
+
+/// A run seed, either a plain number or a Balatro style seed string such as "7LB2WVPK".
+/// Inputs made only of digits are read as numbers, anything else of up to eight
+/// alphanumeric characters is treated as a Balatro seed and upper-cased like the game does.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Seed {
+    Numeric(u64),
+    Balatro(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSeedError(String);
+
+impl fmt::Display for ParseSeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid seed {:?}: expected a u64 or up to {BALATRO_SEED_LEN} alphanumeric characters",
+            self.0
+        )
+    }
+}
+
+impl Error for ParseSeedError {}
+
+impl Seed {
+    pub fn random() -> Seed {
+        let mut rng = rand::thread_rng();
+        let seed = (0..BALATRO_SEED_LEN)
+            .map(|_| BALATRO_SEED_CHARS[rng.gen_range(0..BALATRO_SEED_CHARS.len())] as char)
+            .collect();
+        Seed::Balatro(seed)
+    }
+
+    pub fn to_u64(&self) -> u64 {
+        match self {
+            Seed::Numeric(n) => *n,
+            Seed::Balatro(s) => fnv1a(s.as_bytes()),
+        }
+    }
+
+    pub fn rng(&self) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.to_u64())
+    }
+
+    /// Reads `s` as a Balatro seed even if it is all digits, which the game allows
+    /// (e.g. "12345678") but parsing a Seed would take for a number.
+    pub fn balatro(s: &str) -> Result<Seed, ParseSeedError> {
+        let s = s.trim();
+        if !s.is_empty() && s.len() <= BALATRO_SEED_LEN && s.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Ok(Seed::Balatro(s.to_ascii_uppercase()));
+        }
+
+        Err(ParseSeedError(s.to_string()))
+    }
+}
+
+impl FromStr for Seed {
+    type Err = ParseSeedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let err = || ParseSeedError(s.to_string());
+
+        if s.is_empty() {
+            return Err(err());
+        }
+
+        if s.bytes().all(|b| b.is_ascii_digit()) {
+            return s.parse().map(Seed::Numeric).map_err(|_| err());
+        }
+
+        Seed::balatro(s)
+    }
+}
+
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Seed::Numeric(n) => write!(f, "{n}"),
+            Seed::Balatro(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl From<u64> for Seed {
+    fn from(n: u64) -> Self {
+        Seed::Numeric(n)
+    }
+}
+
+// Seeds are stored as the text they were entered as, so a numeric seed keeps
+// reading back as numeric.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Seed {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Seed {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Bare numbers are read too, so a file can say `seed = 42`.
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Written {
+            Number(u64),
+            Text(String),
+        }
+
+        match Written::deserialize(deserializer)? {
+            Written::Number(n) => Ok(Seed::Numeric(n)),
+            Written::Text(seed) => seed.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deck::Deck;
+
+    use super::Seed;
+
+    #[test]
+    fn parses_numeric_and_balatro_seeds() {
+        assert_eq!("12345".parse(), Ok(Seed::Numeric(12345)));
+        assert_eq!("7lb2wvpk".parse(), Ok(Seed::Balatro("7LB2WVPK".to_string())));
+        assert_eq!(" ABC ".parse(), Ok(Seed::Balatro("ABC".to_string())));
+
+        assert!("".parse::<Seed>().is_err());
+        assert!("TOOLONGSEED".parse::<Seed>().is_err());
+        assert!("AB-CD".parse::<Seed>().is_err());
+        assert!("99999999999999999999999".parse::<Seed>().is_err());
+
+        assert_eq!(Seed::balatro("12345678"), Ok(Seed::Balatro("12345678".to_string())));
+        assert_eq!(Seed::balatro("7lb2wvpk"), Ok(Seed::Balatro("7LB2WVPK".to_string())));
+        assert!(Seed::balatro("").is_err());
+        assert!(Seed::balatro("123456789").is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for seed in [Seed::Numeric(42), Seed::Balatro("7LB2WVPK".to_string()), Seed::random()] {
+            assert_eq!(seed.to_string().parse(), Ok(seed));
+        }
+    }
+
+    #[test]
+    fn same_seed_same_shuffle() {
+        let seed: Seed = "7LB2WVPK".parse().unwrap();
+
+        let mut first = Deck::standard();
+        first.shuffle(&mut seed.rng());
+        let mut second = Deck::standard();
+        second.shuffle(&mut seed.rng());
+        let mut other = Deck::standard();
+        other.shuffle(&mut Seed::Numeric(1).rng());
+
+        assert_eq!(first.cards, second.cards);
+        assert_ne!(first.cards, other.cards);
+    }
+}
@@ -0,0 +1,304 @@
+use std::{ops::Range, thread};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    card_set::CardSet,
+    deck::Deck,
+    hand::Hand,
+    seed::Seed,
+    stats::{Interval, IntervalMethods},
+    valid_hands::HandTypes,
+};
+
+// Samples are handed out in fixed-size chunks, each with its own RNG stream.
+// Results only depend on the master seed and the chunk index, never on which
+// thread happened to run the chunk, so the thread count can change freely.
+const CHUNK_SIZE: usize = 1024;
+
+// When stopping on convergence the intervals are checked after every batch of
+// chunks. The batch is sized in chunks, not threads, to keep runs reproducible.
+const CHUNKS_PER_BATCH: usize = 16;
+
+/// Parameters for a Monte Carlo run. `SimConfig::new` fills in sensible
+/// defaults (standard deck, random seed, all cores, 95% Wilson intervals) for
+/// the rest.
+#[derive(Debug, Clone)]
+pub struct SimConfig {
+    pub samples: usize,
+    pub hand_size: usize,
+    pub deck: Deck,
+    pub threads: usize,
+    pub seed: Seed,
+    pub confidence: f64,
+    pub interval_method: IntervalMethods,
+    /// Stop early once every hand type's interval is narrower than this.
+    /// `samples` is then the most that will be drawn.
+    pub target_width: Option<f64>,
+}
+
+impl SimConfig {
+    pub fn new(samples: usize, hand_size: usize) -> SimConfig {
+        SimConfig {
+            samples,
+            hand_size,
+            deck: Deck::standard(),
+            threads: default_threads(),
+            seed: Seed::random(),
+            confidence: 0.95,
+            interval_method: IntervalMethods::Wilson,
+            target_width: None,
+        }
+    }
+}
+
+pub fn default_threads() -> usize {
+    thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// How many sampled hands contained each hand type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub samples: usize,
+    counts: [usize; HandTypes::ALL.len()],
+}
+
+impl Tally {
+    pub fn record(&mut self, hand: &Hand) {
+        self.record_with(|hand_type| hand_type.detect(hand));
+    }
+
+    /// Same as record, via the bitmask detectors. Only valid for hands of distinct cards.
+    pub fn record_set(&mut self, cards: CardSet) {
+        self.record_with(|hand_type| cards.contains_type(hand_type));
+    }
+
+    fn record_with(&mut self, detect: impl Fn(HandTypes) -> bool) {
+        self.samples += 1;
+
+        let mut found_any = false;
+        for hand_type in &HandTypes::ALL[1..] {
+            if detect(*hand_type) {
+                self.counts[*hand_type as usize] += 1;
+                found_any = true;
+            }
+        }
+
+        if !found_any {
+            self.counts[HandTypes::HighCard as usize] += 1;
+        }
+    }
+
+    /// Records one sample that held each hand type marked in `seen`, indexed as
+    /// [`HandTypes::ALL`]. Unlike record, High Card can count alongside better
+    /// hands, for samples made of several hands.
+    pub fn record_seen(&mut self, seen: [bool; HandTypes::ALL.len()]) {
+        self.samples += 1;
+        for (count, seen) in self.counts.iter_mut().zip(seen) {
+            *count += usize::from(seen);
+        }
+    }
+
+    pub fn count(&self, hand_type: HandTypes) -> usize {
+        self.counts[hand_type as usize]
+    }
+
+    pub fn frequency(&self, hand_type: HandTypes) -> f64 {
+        if self.samples == 0 {
+            return 0.;
+        }
+        self.count(hand_type) as f64 / self.samples as f64
+    }
+
+    pub fn interval(
+        &self,
+        hand_type: HandTypes,
+        method: IntervalMethods,
+        confidence: f64,
+    ) -> Interval {
+        method.interval(self.count(hand_type), self.samples, confidence)
+    }
+
+    pub fn converged(&self, method: IntervalMethods, confidence: f64, target_width: f64) -> bool {
+        HandTypes::ALL
+            .iter()
+            .all(|t| self.interval(*t, method, confidence).width() < target_width)
+    }
+
+    pub fn merge(&mut self, other: &Tally) {
+        self.samples += other.samples;
+        for (count, other_count) in self.counts.iter_mut().zip(other.counts) {
+            *count += other_count;
+        }
+    }
+}
+
+pub(crate) fn chunk_rng(seed: u64, chunk: usize) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(chunk as u64);
+    rng
+}
+
+/// Splits `samples` into chunks, runs `job(rng, samples_in_chunk)` for each on
+/// a pool of scoped threads, and returns the per-chunk results in chunk order.
+pub(crate) fn run_chunks<T, F>(samples: usize, threads: usize, seed: u64, job: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(&mut ChaCha8Rng, usize) -> T + Sync,
+{
+    run_chunk_range(
+        0..samples.div_ceil(CHUNK_SIZE),
+        samples,
+        threads,
+        seed,
+        &job,
+    )
+}
+
+fn run_chunk_range<T, F>(
+    chunks: Range<usize>,
+    samples: usize,
+    threads: usize,
+    seed: u64,
+    job: &F,
+) -> Vec<T>
+where
+    T: Send,
+    F: Fn(&mut ChaCha8Rng, usize) -> T + Sync,
+{
+    run_indexed(chunks, threads, seed, &|rng, chunk| {
+        job(rng, CHUNK_SIZE.min(samples - chunk * CHUNK_SIZE))
+    })
+}
+
+/// Runs `job(rng, index)` for every index in `jobs`, each with the RNG stream for
+/// that index, and returns the results in index order.
+pub(crate) fn run_indexed<T, F>(jobs: Range<usize>, threads: usize, seed: u64, job: &F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(&mut ChaCha8Rng, usize) -> T + Sync,
+{
+    let threads = threads.clamp(1, jobs.len().max(1));
+
+    let mut results: Vec<(usize, T)> = thread::scope(|s| {
+        let workers: Vec<_> = (0..threads)
+            .map(|worker| {
+                let jobs = jobs.clone();
+                s.spawn(move || {
+                    jobs.skip(worker)
+                        .step_by(threads)
+                        .map(|index| (index, job(&mut chunk_rng(seed, index), index)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("simulation worker panicked"))
+            .collect()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn sample_hands(config: &SimConfig, rng: &mut ChaCha8Rng, n: usize) -> Tally {
+    // Reshuffling the same deck is as good as a fresh one and saves the
+    // allocation, and only the cards actually drawn need shuffling.
+    let mut deck = config.deck.clone();
+    // The bitmask path can't hold duplicate cards, e.g. from the Checkered Deck.
+    let distinct = CardSet::from_cards(&deck.cards).len() == deck.cards.len();
+    let mut tally = Tally::default();
+    for _ in 0..n {
+        let (drawn, _) = deck.cards.partial_shuffle(rng, config.hand_size);
+        if distinct {
+            tally.record_set(CardSet::from_cards(drawn));
+        } else {
+            tally.record(&Hand {
+                cards: drawn.to_vec(),
+                size: config.hand_size,
+            });
+        }
+    }
+    tally
+}
+
+/// Draws `config.samples` hands of `config.hand_size` from shuffled copies of
+/// `config.deck` and tallies the hand types they contain. The result only depends on
+/// the seed, not the thread count.
+pub fn simulate(config: &SimConfig) -> Tally {
+    let seed = config.seed.to_u64();
+    let chunks = config.samples.div_ceil(CHUNK_SIZE);
+    let batch_size = match config.target_width {
+        Some(_) => CHUNKS_PER_BATCH,
+        None => chunks.max(1),
+    };
+    let job = |rng: &mut ChaCha8Rng, n| sample_hands(config, rng, n);
+
+    let mut tally = Tally::default();
+    for batch_start in (0..chunks).step_by(batch_size) {
+        let batch = batch_start..(batch_start + batch_size).min(chunks);
+        for chunk_tally in run_chunk_range(batch, config.samples, config.threads, seed, &job) {
+            tally.merge(&chunk_tally);
+        }
+
+        if let Some(target_width) = config.target_width {
+            if tally.converged(config.interval_method, config.confidence, target_width) {
+                break;
+            }
+        }
+    }
+    tally
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{seed::Seed, valid_hands::HandTypes};
+
+    use super::{simulate, SimConfig};
+
+    #[test]
+    fn thread_count_does_not_change_results() {
+        let mut config = SimConfig::new(3000, 8);
+        config.seed = Seed::Numeric(42);
+
+        config.threads = 1;
+        let single = simulate(&config);
+        config.threads = 4;
+        let multi = simulate(&config);
+
+        assert_eq!(single.samples, 3000);
+        assert_eq!(single, multi);
+    }
+
+    #[test]
+    fn different_seeds_give_different_runs() {
+        let mut config = SimConfig::new(2000, 8);
+        config.seed = Seed::Numeric(1);
+        let first = simulate(&config);
+        config.seed = "7LB2WVPK".parse().unwrap();
+        let second = simulate(&config);
+
+        assert_ne!(first, second);
+        assert!(first.count(HandTypes::Pair) > 0);
+    }
+
+    #[test]
+    fn stops_once_intervals_are_narrow_enough() {
+        let mut config = SimConfig::new(1_000_000, 8);
+        config.seed = Seed::Numeric(3);
+        config.target_width = Some(0.05);
+
+        config.threads = 1;
+        let tally = simulate(&config);
+        assert!(tally.samples < config.samples);
+        assert!(tally.converged(config.interval_method, config.confidence, 0.05));
+
+        config.threads = 3;
+        assert_eq!(simulate(&config), tally);
+    }
+}
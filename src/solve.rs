@@ -0,0 +1,236 @@
+//! Discard recommendations: for every way of discarding up to MAX_DISCARD held
+//! cards, refill the hand from the rest of the deck many times and measure how
+//! good the resulting hand is.
+
+use std::{error::Error, fmt};
+
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    card::Card,
+    card_set::CardSet,
+    hand::Hand,
+    lookup::best_hand,
+    score::base_score,
+    simulation::{run_indexed, SimConfig},
+    valid_hands::HandTypes,
+};
+
+/// Most cards that can be discarded at once.
+pub const MAX_DISCARD: usize = 5;
+/// Most held cards [`recommend`] takes. Options grow with the number held, and
+/// no hand size in the game gets near this.
+pub const MAX_HELD: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveError {
+    /// More cards held than the hand size, or than [`MAX_HELD`].
+    TooManyHeld { held: usize, max: usize },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::TooManyHeld { held, max } => {
+                write!(f, "{held} held cards is more than the {max} allowed")
+            }
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objectives {
+    /// Average base chips x mult of the best hand type after the redraw.
+    Score,
+    /// Chance of ending up with this hand type or better.
+    AtLeast(HandTypes),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscardOption {
+    pub discard: Vec<Card>,
+    pub value: f64,
+}
+
+// Every way of splitting `cards` into (discarded, kept) with at most `max`
+// discarded, smallest discard first.
+fn splits(cards: &[Card], max: usize) -> Vec<(Vec<Card>, Vec<Card>)> {
+    let mut splits: Vec<(Vec<Card>, Vec<Card>)> = (0u32..1 << cards.len())
+        .filter(|mask| mask.count_ones() as usize <= max)
+        .map(|mask| {
+            let (discard, kept): (Vec<_>, Vec<_>) =
+                (0..cards.len()).partition(|i| mask >> i & 1 == 1);
+            (
+                discard.into_iter().map(|i| cards[i]).collect(),
+                kept.into_iter().map(|i| cards[i]).collect(),
+            )
+        })
+        .collect();
+    splits.sort_by_key(|(discard, _)| discard.len());
+    splits
+}
+
+/// Ranks every discard of the held cards, best first. Each redraw refills the
+/// hand to `config.hand_size` from `config.deck` with the held cards taken out,
+/// `config.samples` per option.
+/// Fails if more cards are held than `config.hand_size` or [`MAX_HELD`].
+pub fn recommend(
+    held: &[Card],
+    objective: Objectives,
+    config: &SimConfig,
+) -> Result<Vec<DiscardOption>, SolveError> {
+    let max = config.hand_size.min(MAX_HELD);
+    if held.len() > max {
+        return Err(SolveError::TooManyHeld {
+            held: held.len(),
+            max,
+        });
+    }
+
+    let mut deck = config.deck.cards.clone();
+    for card in held {
+        // By rank and suit alone: a held card may have picked up an
+        // enhancement, edition or seal the deck's copy doesn't show.
+        if let Some(position) = deck
+            .iter()
+            .position(|c| c.rank == card.rank && c.suit == card.suit)
+        {
+            deck.swap_remove(position);
+        }
+    }
+
+    let all_cards: Vec<Card> = held.iter().chain(&deck).copied().collect();
+    let distinct = CardSet::from_cards(&all_cards).len() == all_cards.len();
+    let best = |cards: Vec<Card>| {
+        if distinct {
+            best_hand(CardSet::from_cards(&cards))
+        } else {
+            let size = cards.len();
+            HandTypes::best(&Hand { cards, size })
+        }
+    };
+    let value = |hand_type: HandTypes| match objective {
+        Objectives::Score => {
+            let (chips, mult) = base_score(hand_type);
+            chips * mult
+        }
+        Objectives::AtLeast(target) => f64::from(u8::from(hand_type >= target)),
+    };
+
+    let options = splits(held, MAX_DISCARD.min(deck.len()));
+    let values = run_indexed(
+        0..options.len(),
+        config.threads,
+        config.seed.to_u64(),
+        &|rng: &mut ChaCha8Rng, index| {
+            let (_, kept) = &options[index];
+            let mut deck = deck.clone();
+            let refill = (config.hand_size - kept.len()).min(deck.len());
+            let mut total = 0.;
+            for _ in 0..config.samples {
+                let (drawn, _) = deck.partial_shuffle(rng, refill);
+                total += value(best(kept.iter().chain(drawn.iter()).copied().collect()));
+            }
+            total / config.samples as f64
+        },
+    );
+
+    let mut ranked: Vec<DiscardOption> = options
+        .into_iter()
+        .zip(values)
+        .map(|((discard, _), value)| DiscardOption { discard, value })
+        .collect();
+    // Stable, so equally good options keep the smaller discard first.
+    ranked.sort_by(|a, b| b.value.total_cmp(&a.value));
+    Ok(ranked)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        card::Card, deck::Deck, modifiers::Enhancements, seed::Seed, simulation::SimConfig,
+        suit::Suits, valid_hands::HandTypes,
+    };
+
+    use super::{recommend, Objectives, SolveError, MAX_HELD};
+
+    #[test]
+    fn chases_a_four_card_flush() {
+        let held = [
+            (3, Suits::Hearts),
+            (5, Suits::Hearts),
+            (11, Suits::Hearts),
+            (13, Suits::Hearts),
+            (2, Suits::Clubs),
+            (7, Suits::Diamonds),
+            (9, Suits::Spades),
+            (12, Suits::Clubs),
+        ]
+        .map(|(v, s)| Card::from_card_value(v, Some(s)));
+
+        let mut config = SimConfig::new(2000, 8);
+        config.seed = Seed::Numeric(7);
+        let ranked = recommend(&held, Objectives::AtLeast(HandTypes::Flush), &config).unwrap();
+
+        let best = &ranked[0];
+        let mut discard = best.discard.clone();
+        discard.sort();
+        let mut off_suit = held[4..].to_vec();
+        off_suit.sort();
+        assert_eq!(discard, off_suit);
+
+        // One or more of the 9 remaining Hearts in 4 draws from 44 cards.
+        let exact = 1. - 52360. / 135751.;
+        assert!((best.value - exact).abs() < 0.04, "{best:?}");
+    }
+
+    #[test]
+    fn takes_held_cards_out_of_the_deck() {
+        // A Glass Ace of Spades held from a deck with a plain one: the held
+        // copy still leaves the deck, so no redraw can pair it.
+        let mut ace: Card = "AS".parse().unwrap();
+        ace.enhancement = Some(Enhancements::Glass);
+        let held = [ace, "2H".parse().unwrap()];
+        let mut config = SimConfig::new(100, 2);
+        config.deck = Deck {
+            cards: ["AS", "2H", "3C"].map(|c| c.parse().unwrap()).to_vec(),
+        };
+        let ranked = recommend(&held, Objectives::AtLeast(HandTypes::Pair), &config).unwrap();
+        // Keep both, or swap either for the 3C.
+        assert_eq!(ranked.len(), 3);
+        assert!(ranked.iter().all(|option| option.value == 0.), "{ranked:?}");
+
+        let too_many = Deck::standard().cards[..MAX_HELD + 1].to_vec();
+        let config = SimConfig::new(100, too_many.len());
+        assert_eq!(
+            recommend(&too_many, Objectives::Score, &config),
+            Err(SolveError::TooManyHeld {
+                held: MAX_HELD + 1,
+                max: MAX_HELD
+            })
+        );
+        let config = SimConfig::new(100, 8);
+        assert_eq!(
+            recommend(&too_many[..9], Objectives::Score, &config),
+            Err(SolveError::TooManyHeld { held: 9, max: 8 })
+        );
+    }
+
+    #[test]
+    fn refills_the_hand_to_its_size() {
+        // Four Hearts held in a hand of 8: keeping them all still draws 4.
+        let held = [3, 5, 11, 13].map(|v| Card::from_card_value(v, Some(Suits::Hearts)));
+        let mut config = SimConfig::new(2000, 8);
+        config.seed = Seed::Numeric(7);
+        let ranked = recommend(&held, Objectives::AtLeast(HandTypes::Flush), &config).unwrap();
+
+        let best = &ranked[0];
+        assert!(best.discard.is_empty(), "{best:?}");
+        // One or more of the 9 remaining Hearts in 4 draws from 48 cards.
+        let exact = 1. - 82251. / 194580.;
+        assert!((best.value - exact).abs() < 0.04, "{best:?}");
+    }
+}
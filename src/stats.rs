@@ -0,0 +1,398 @@
+//! Binomial confidence intervals for the hit rates reported by the simulator, and
+//! the paired tests that compare policies in a [`crate::tournament`].
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Interval {
+    pub fn width(&self) -> f64 {
+        self.upper - self.lower
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum IntervalMethods {
+    Wilson,
+    ClopperPearson,
+}
+
+impl IntervalMethods {
+    pub fn to_str(self) -> &'static str {
+        match self {
+            IntervalMethods::Wilson => "wilson",
+            IntervalMethods::ClopperPearson => "clopper-pearson",
+        }
+    }
+
+    pub fn interval(self, successes: usize, trials: usize, confidence: f64) -> Interval {
+        match self {
+            IntervalMethods::Wilson => wilson(successes, trials, confidence),
+            IntervalMethods::ClopperPearson => clopper_pearson(successes, trials, confidence),
+        }
+    }
+}
+
+pub fn wilson(successes: usize, trials: usize, confidence: f64) -> Interval {
+    if trials == 0 {
+        return Interval {
+            lower: 0.,
+            upper: 1.,
+        };
+    }
+
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z = normal_quantile(1. - (1. - confidence) / 2.);
+    let z2 = z * z;
+
+    let centre = (p + z2 / (2. * n)) / (1. + z2 / n);
+    let half_width = z / (1. + z2 / n) * (p * (1. - p) / n + z2 / (4. * n * n)).sqrt();
+
+    Interval {
+        lower: (centre - half_width).max(0.),
+        upper: (centre + half_width).min(1.),
+    }
+}
+
+/// Exact interval from the beta distribution quantiles.
+pub fn clopper_pearson(successes: usize, trials: usize, confidence: f64) -> Interval {
+    if trials == 0 {
+        return Interval {
+            lower: 0.,
+            upper: 1.,
+        };
+    }
+
+    let alpha = 1. - confidence;
+    let x = successes as f64;
+    let n = trials as f64;
+
+    let lower = if successes == 0 {
+        0.
+    } else {
+        beta_quantile(alpha / 2., x, n - x + 1.)
+    };
+    let upper = if successes == trials {
+        1.
+    } else {
+        beta_quantile(1. - alpha / 2., x + 1., n - x)
+    };
+
+    Interval { lower, upper }
+}
+
+/// Binomial coefficient as a float, exact for the deck sizes we deal with.
+pub fn choose(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.;
+    }
+    let k = k.min(n - k);
+    (0..k).fold(1., |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+/// Acklam's rational approximation to the inverse standard normal CDF.
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    if p <= 0. {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1. {
+        return f64::INFINITY;
+    }
+
+    if p < P_LOW {
+        let q = (-2. * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.)
+    } else if p <= 1. - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.)
+    } else {
+        -normal_quantile(1. - p)
+    }
+}
+
+/// Lanczos approximation (g = 7, n = 9).
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.5203681218851,
+        -1259.1392167224028,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507343278686905,
+        -0.13857109526572012,
+        9.984_369_578_019_572e-6,
+        1.5056327351493116e-7,
+    ];
+
+    if x < 0.5 {
+        // Reflection formula.
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1. - x);
+    }
+
+    let x = x - 1.;
+    let mut sum = COEFFICIENTS[0];
+    for (i, c) in COEFFICIENTS.iter().enumerate().skip(1) {
+        sum += c / (x + i as f64);
+    }
+    let t = x + 7.5;
+    0.5 * (2. * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// Regularized incomplete beta function I_x(a, b), evaluated with the continued
+/// fraction from Numerical Recipes.
+pub fn incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0. {
+        return 0.;
+    }
+    if x >= 1. {
+        return 1.;
+    }
+
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1. - x).ln();
+    if x < (a + 1.) / (a + b + 2.) {
+        ln_front.exp() * beta_continued_fraction(x, a, b) / a
+    } else {
+        1. - ln_front.exp() * beta_continued_fraction(1. - x, b, a) / b
+    }
+}
+
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    const EPSILON: f64 = 1e-15;
+
+    let qab = a + b;
+    let qap = a + 1.;
+    let qam = a - 1.;
+    let mut c = 1.;
+    let mut d = 1. - qab * x / qap;
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1. / d;
+    let mut h = d;
+
+    for m in 1..=300 {
+        let m = m as f64;
+        let m2 = 2. * m;
+
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1. + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1. + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1. / d;
+        h *= d * c;
+
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1. + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1. + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1. / d;
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.).abs() < EPSILON {
+            break;
+        }
+    }
+
+    h
+}
+
+/// Inverts the incomplete beta function by bisection, which is plenty fast for
+/// the handful of quantiles a report needs.
+pub fn beta_quantile(p: f64, a: f64, b: f64) -> f64 {
+    let (mut lo, mut hi) = (0., 1.);
+    for _ in 0..100 {
+        let mid = (lo + hi) / 2.;
+        if incomplete_beta(mid, a, b) < p {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    (lo + hi) / 2.
+}
+
+/// Exact two-sided McNemar test on paired wins: the chance of a split at least
+/// this uneven between the pairs only the first side won and the pairs only the
+/// second side won, if either side is as likely to win a split pair.
+pub fn mcnemar(only_first: usize, only_second: usize) -> f64 {
+    let n = only_first + only_second;
+    if n == 0 {
+        return 1.;
+    }
+    // P(X <= k) for X ~ Binomial(n, 1/2).
+    let k = only_first.min(only_second);
+    let tail = incomplete_beta(0.5, (n - k) as f64, (k + 1) as f64);
+    (2. * tail).min(1.)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PairedTest {
+    pub mean_difference: f64,
+    pub t: f64,
+    /// Two-sided.
+    pub p: f64,
+}
+
+/// Student's t-test on the differences between paired samples.
+pub fn paired_t(differences: &[f64]) -> PairedTest {
+    let n = differences.len() as f64;
+    let mean = differences.iter().sum::<f64>() / n.max(1.);
+    let variance = differences.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / (n - 1.);
+    if n < 2. || variance == 0. {
+        // Every pair differed by the same amount, so the test is all or nothing.
+        let differs = n >= 2. && mean != 0.;
+        return PairedTest {
+            mean_difference: mean,
+            t: if differs {
+                f64::INFINITY.copysign(mean)
+            } else {
+                0.
+            },
+            p: if differs { 0. } else { 1. },
+        };
+    }
+
+    let t = mean / (variance / n).sqrt();
+    let df = n - 1.;
+    PairedTest {
+        mean_difference: mean,
+        t,
+        p: incomplete_beta(df / (df + t * t), df / 2., 0.5),
+    }
+}
+
+/// The q quantile of sorted samples, interpolating linearly between them.
+pub fn percentile(sorted: &[f64], q: f64) -> f64 {
+    match sorted.len() {
+        0 => f64::NAN,
+        n => {
+            let rank = q.clamp(0., 1.) * (n - 1) as f64;
+            let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+            sorted[below] + (sorted[above] - sorted[below]) * rank.fract()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        clopper_pearson, incomplete_beta, mcnemar, normal_quantile, paired_t, percentile, wilson,
+    };
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    #[test]
+    fn normal_quantile_matches_tables() {
+        assert!(close(normal_quantile(0.975), 1.959964, 1e-6));
+        assert!(close(normal_quantile(0.5), 0., 1e-9));
+        assert!(close(normal_quantile(0.005), -2.575829, 1e-6));
+    }
+
+    #[test]
+    fn incomplete_beta_known_values() {
+        // I_x(1, 1) is the uniform CDF and I_x(2, 1) = x^2.
+        assert!(close(incomplete_beta(0.3, 1., 1.), 0.3, 1e-12));
+        assert!(close(incomplete_beta(0.3, 2., 1.), 0.09, 1e-12));
+        assert!(close(incomplete_beta(0.5, 7., 7.), 0.5, 1e-12));
+    }
+
+    #[test]
+    fn intervals_match_reference_values() {
+        // Reference values from exact binomial tail sums and the closed-form Wilson score.
+        let cp = clopper_pearson(7, 50, 0.95);
+        assert!(close(cp.lower, 0.05819, 1e-4), "{cp:?}");
+        assert!(close(cp.upper, 0.26740, 1e-4), "{cp:?}");
+
+        let w = wilson(7, 50, 0.95);
+        assert!(close(w.lower, 0.06951, 1e-4), "{w:?}");
+        assert!(close(w.upper, 0.26186, 1e-4), "{w:?}");
+    }
+
+    #[test]
+    fn intervals_handle_edges() {
+        let none = clopper_pearson(0, 100, 0.95);
+        assert_eq!(none.lower, 0.);
+        assert!(close(none.upper, 0.03622, 1e-4));
+
+        let all = wilson(100, 100, 0.95);
+        assert_eq!(all.upper, 1.);
+        assert!(all.lower > 0.95);
+    }
+
+    #[test]
+    fn paired_tests_match_reference_values() {
+        // 5 of 5 split pairs going one way happens 1 time in 16, either way.
+        assert!(close(mcnemar(0, 5), 0.0625, 1e-12));
+        assert!(close(mcnemar(7, 2), 0.179688, 1e-6));
+        assert_eq!(mcnemar(3, 3), 1.);
+        assert_eq!(mcnemar(0, 0), 1.);
+
+        // scipy.stats.ttest_1samp([1, 2, 3, 4, 5], 0).
+        let test = paired_t(&[1., 2., 3., 4., 5.]);
+        assert!(close(test.t, 4.242641, 1e-6), "{test:?}");
+        assert!(close(test.p, 0.013236, 1e-6), "{test:?}");
+        assert_eq!(paired_t(&[2., 2.]).p, 0.);
+        assert_eq!(paired_t(&[0., 0.]).p, 1.);
+        assert_eq!(paired_t(&[]).p, 1.);
+
+        assert_eq!(percentile(&[1., 2., 3., 4.], 0.5), 2.5);
+        assert_eq!(percentile(&[1., 2., 3., 4.], 1.), 4.);
+        assert!(percentile(&[], 0.5).is_nan());
+    }
+}
@@ -1,4 +1,5 @@
 #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Suits {
     Spades,
     Hearts,
@@ -8,7 +9,7 @@ pub enum Suits {
 }
 
 impl Suits {
-    pub fn to_str(&self) -> &str {
+    pub fn to_str(self) -> &'static str {
         match self {
             Suits::Spades => "Spades",
             Suits::Hearts => "Hearts",
@@ -20,6 +21,7 @@ impl Suits {
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Ranks {
     Ace,
     King,
@@ -83,7 +85,7 @@ impl Ranks {
         }
     }
 
-    pub fn to_str(&self) -> &str {
+    pub fn to_str(self) -> &'static str {
         match self {
             Ranks::Ace => "Ace",
             Ranks::King => "King",
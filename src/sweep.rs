@@ -0,0 +1,281 @@
+//! Hand type probabilities over a grid of setups in one go: every combination
+//! of hand size, ranks taken out of the deck and discard count.
+//!
+//! With no discards a point is the opening draw and its odds are exact, from
+//! [`crate::odds::exact`]. With D discards it is the chance of holding each
+//! hand type in any of the first D + 1 hands, the opening one and those after
+//! each discard, simulated with a [`Policy`] deciding what to throw away as in
+//! [`crate::draws`].
+
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::{
+    card::Card,
+    deck::Deck,
+    draws::seen_within,
+    odds,
+    policy::Policy,
+    round::RoundSetup,
+    seed::Seed,
+    simulation::{run_indexed, Tally},
+    suit::{Ranks, Suits},
+    valid_hands::HandTypes,
+};
+
+/// Ranks taken out of the deck entirely, written like "2,3,4" or "J,Q,K".
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Removed(pub Vec<Ranks>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRemovedError(String);
+
+impl fmt::Display for ParseRemovedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid rank {:?}: expected ranks such as 2, 10, T, J, Q, K or A separated by commas",
+            self.0
+        )
+    }
+}
+
+impl Error for ParseRemovedError {}
+
+impl FromStr for Removed {
+    type Err = ParseRemovedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ranks = vec![];
+        for token in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            // Ranks are written as in card notation, so borrow its parser.
+            let rank = format!("{token}S")
+                .parse::<Card>()
+                .ok()
+                .filter(|card| card.rank != Ranks::Blank && card.suit == Suits::Spades)
+                .ok_or_else(|| ParseRemovedError(token.to_string()))?
+                .rank;
+            if !ranks.contains(&rank) {
+                ranks.push(rank);
+            }
+        }
+        Ok(Removed(ranks))
+    }
+}
+
+impl fmt::Display for Removed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbols: Vec<&str> = self
+            .0
+            .iter()
+            .map(|rank| match rank {
+                Ranks::Ace => "A",
+                Ranks::King => "K",
+                Ranks::Queen => "Q",
+                Ranks::Jack => "J",
+                rank => rank.to_str(),
+            })
+            .collect();
+        f.write_str(&symbols.join(","))
+    }
+}
+
+impl Removed {
+    pub fn apply(&self, deck: &Deck) -> Deck {
+        let mut deck = deck.clone();
+        deck.cards.retain(|card| !self.0.contains(&card.rank));
+        deck
+    }
+}
+
+/// One setup in a sweep.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Point {
+    pub hand_size: usize,
+    pub removed: Removed,
+    pub discards: u32,
+}
+
+impl Point {
+    /// Every combination of the values given, hand size varying slowest.
+    pub fn grid(hand_sizes: &[usize], removals: &[Removed], discards: &[u32]) -> Vec<Point> {
+        let mut points = vec![];
+        for hand_size in hand_sizes {
+            for removed in removals {
+                for discards in discards {
+                    points.push(Point {
+                        hand_size: *hand_size,
+                        removed: removed.clone(),
+                        discards: *discards,
+                    });
+                }
+            }
+        }
+        points
+    }
+
+    /// Short enough for a column heading, e.g. "8" or "10 -2,3 d2".
+    pub fn label(&self) -> String {
+        let mut label = self.hand_size.to_string();
+        if !self.removed.0.is_empty() {
+            label += &format!(" -{}", self.removed);
+        }
+        if self.discards > 0 {
+            label += &format!(" d{}", self.discards);
+        }
+        label
+    }
+}
+
+/// The chance of holding each hand type at one point, indexed as
+/// [`HandTypes::ALL`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub point: Point,
+    pub deck_size: usize,
+    /// Whether the probabilities are exact rather than simulated.
+    pub exact: bool,
+    pub probabilities: [f64; HandTypes::ALL.len()],
+}
+
+/// Every point of a sweep, in the order given.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sweep {
+    pub rows: Vec<Row>,
+}
+
+impl Sweep {
+    /// Works out each point from `deck`. Simulated points play `rounds` rounds
+    /// with the policy, and every point deals round i from the same seed.
+    pub fn run<P: Policy + Clone + Sync>(
+        deck: &Deck,
+        points: &[Point],
+        policy: &P,
+        rounds: usize,
+        seed: &Seed,
+        threads: usize,
+    ) -> Sweep {
+        let rows = points
+            .iter()
+            .map(|point| {
+                let deck = point.removed.apply(deck);
+                let deck_size = deck.cards.len();
+                let mut probabilities = [0.; HandTypes::ALL.len()];
+                if point.discards == 0 {
+                    for (hand_type, p) in odds::exact(&deck, point.hand_size) {
+                        probabilities[hand_type as usize] = p;
+                    }
+                } else {
+                    let setup = RoundSetup {
+                        hand_size: point.hand_size,
+                        discards: point.discards,
+                        ..RoundSetup::new(deck, f64::INFINITY)
+                    };
+                    let draws = point.discards as usize + 1;
+                    let mut tally = Tally::default();
+                    let seen = run_indexed(0..rounds, threads, seed.to_u64(), &|rng, _| {
+                        seen_within(&setup, &mut policy.clone(), draws, rng)
+                    });
+                    for seen in seen {
+                        tally.record_seen(seen);
+                    }
+                    for hand_type in HandTypes::ALL {
+                        probabilities[hand_type as usize] = tally.frequency(hand_type);
+                    }
+                }
+                Row {
+                    point: point.clone(),
+                    deck_size,
+                    exact: point.discards == 0,
+                    probabilities,
+                }
+            })
+            .collect();
+        Sweep { rows }
+    }
+
+    /// Hand types down the side, best first, and one column per point.
+    pub fn to_table(&self) -> String {
+        let labels: Vec<String> = self.rows.iter().map(|row| row.point.label()).collect();
+        let width = labels.iter().map(String::len).max().unwrap_or(0).max(8) + 2;
+
+        let mut table = format!("{:>15} ", "");
+        for label in &labels {
+            table += &format!("{label:>width$}");
+        }
+        table.push('\n');
+        for hand_type in HandTypes::ALL.iter().rev() {
+            table += &format!("{:>15}:", hand_type.to_str());
+            for row in &self.rows {
+                let p = row.probabilities[*hand_type as usize] * 100.;
+                table += &format!("{:>w$.3}%", p, w = width - 1);
+            }
+            table.push('\n');
+        }
+        table
+    }
+
+    /// One line per point and hand type.
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            String::from("hand_size,removed,discards,deck_size,method,hand_type,probability\n");
+        for row in &self.rows {
+            for hand_type in HandTypes::ALL {
+                csv += &format!(
+                    "{},\"{}\",{},{},{},{},{}\n",
+                    row.point.hand_size,
+                    row.point.removed,
+                    row.point.discards,
+                    row.deck_size,
+                    if row.exact { "exact" } else { "simulated" },
+                    hand_type.to_str(),
+                    row.probabilities[hand_type as usize],
+                );
+            }
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{deck::Deck, odds, policy::ChaseFlush, seed::Seed, valid_hands::HandTypes};
+
+    use super::{Point, Removed, Sweep};
+
+    #[test]
+    fn parses_removed_ranks() {
+        let removed: Removed = "2, 3,10,k,2".parse().unwrap();
+        assert_eq!(removed.to_string(), "2,3,10,K");
+        assert_eq!(removed.apply(&Deck::standard()).cards.len(), 36);
+        assert_eq!("".parse::<Removed>().unwrap(), Removed::default());
+        assert!("2,X".parse::<Removed>().is_err());
+        assert!("?".parse::<Removed>().is_err());
+    }
+
+    #[test]
+    fn sweeps_every_combination() {
+        let removals = [Removed::default(), "2,3".parse().unwrap()];
+        let points = Point::grid(&[5, 6], &removals, &[0, 1]);
+        assert_eq!(points.len(), 8);
+        assert_eq!(points[3].label(), "5 -2,3 d1");
+
+        // Twos to Nines keep the exact points quick.
+        let mut deck = Deck::standard();
+        deck.cards.retain(|c| c.value <= 9);
+        let sweep = Sweep::run(&deck, &points, &ChaseFlush, 400, &Seed::Numeric(2), 2);
+        let flush = |i: usize| sweep.rows[i].probabilities[HandTypes::Flush as usize];
+
+        // The exact points match the odds calculator.
+        assert!(sweep.rows[0].exact);
+        assert_eq!(flush(4), odds::exact(&deck, 6)[HandTypes::Flush as usize].1);
+        assert_eq!(sweep.rows[2].deck_size, 24);
+        // More cards and a discard both help.
+        assert!(flush(4) > flush(0));
+        assert!(!sweep.rows[5].exact);
+        assert!(flush(5) > flush(4));
+
+        let table = sweep.to_table();
+        assert_eq!(table.lines().count(), 1 + HandTypes::ALL.len());
+        assert_eq!(sweep.to_csv().lines().count(), 1 + 8 * HandTypes::ALL.len());
+    }
+}
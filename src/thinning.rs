@@ -0,0 +1,195 @@
+//! How taking cards out of the deck, or adding them, moves the odds of drawing
+//! each hand type: the question behind destroying cards with The Hanged Man or
+//! Trading Card, or copying them with DNA. Every change is worked out exactly
+//! with [`crate::odds::exact`], so even the small effect of a single card is
+//! free of sampling noise.
+
+use std::fmt;
+
+use crate::{
+    card::Card,
+    deck::Deck,
+    odds,
+    suit::{Ranks, Suits},
+    valid_hands::HandTypes,
+};
+
+/// One change to the deck.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    /// One copy of the card.
+    Remove(Card),
+    RemoveRank(Ranks),
+    RemoveSuit(Suits),
+    /// Another copy of the card.
+    Add(Card),
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Remove(card) => write!(f, "remove {}", card.to_notation()),
+            Change::RemoveRank(rank) => write!(f, "remove every {}", rank.to_str()),
+            Change::RemoveSuit(suit) => write!(f, "remove every {}", suit.to_str()),
+            Change::Add(card) => write!(f, "add {}", card.to_notation()),
+        }
+    }
+}
+
+impl Change {
+    /// Every change that makes a difference to the deck: removing or adding a
+    /// copy of each card in it, and removing each rank and suit it holds.
+    pub fn candidates(deck: &Deck) -> Vec<Change> {
+        let mut cards = deck.cards.clone();
+        cards.sort();
+        cards.dedup();
+        let mut ranks: Vec<Ranks> = cards.iter().map(|c| c.rank).collect();
+        ranks.sort();
+        ranks.dedup();
+        let mut suits: Vec<Suits> = cards.iter().map(|c| c.suit).collect();
+        suits.sort();
+        suits.dedup();
+
+        let mut changes: Vec<Change> = cards.iter().map(|c| Change::Remove(*c)).collect();
+        changes.extend(ranks.into_iter().map(Change::RemoveRank));
+        changes.extend(suits.into_iter().map(Change::RemoveSuit));
+        changes.extend(cards.iter().map(|c| Change::Add(*c)));
+        changes
+    }
+
+    pub fn apply(&self, deck: &Deck) -> Deck {
+        let mut deck = deck.clone();
+        match self {
+            Change::Remove(card) => {
+                if let Some(i) = deck.cards.iter().position(|c| c == card) {
+                    deck.cards.remove(i);
+                }
+            }
+            Change::RemoveRank(rank) => deck.cards.retain(|c| c.rank != *rank),
+            Change::RemoveSuit(suit) => deck.cards.retain(|c| c.suit != *suit),
+            Change::Add(card) => deck.cards.push(*card),
+        }
+        deck
+    }
+}
+
+/// The odds of a draw after one change, indexed as [`HandTypes::ALL`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Impact {
+    pub change: Change,
+    pub deck_size: usize,
+    pub probabilities: [f64; HandTypes::ALL.len()],
+    /// How far each probability moved from the unchanged deck's.
+    pub deltas: [f64; HandTypes::ALL.len()],
+}
+
+impl Impact {
+    pub fn delta(&self, hand_type: HandTypes) -> f64 {
+        self.deltas[hand_type as usize]
+    }
+}
+
+/// The odds of drawing `hand_size` cards from `deck` for each hand type,
+/// indexed as [`HandTypes::ALL`].
+pub fn probabilities(deck: &Deck, hand_size: usize) -> [f64; HandTypes::ALL.len()] {
+    let mut probabilities = [0.; HandTypes::ALL.len()];
+    for (hand_type, p) in odds::exact(deck, hand_size) {
+        probabilities[hand_type as usize] = p;
+    }
+    probabilities
+}
+
+/// The effect of each change on a `hand_size` card draw, in the order given.
+pub fn impacts(deck: &Deck, hand_size: usize, changes: &[Change]) -> Vec<Impact> {
+    let before = probabilities(deck, hand_size);
+    changes
+        .iter()
+        .map(|change| {
+            let deck = change.apply(deck);
+            let probabilities = probabilities(&deck, hand_size);
+            Impact {
+                change: *change,
+                deck_size: deck.cards.len(),
+                probabilities,
+                deltas: std::array::from_fn(|i| probabilities[i] - before[i]),
+            }
+        })
+        .collect()
+}
+
+/// Every candidate change, the one that raises the odds of `target` most
+/// first.
+pub fn ranked(deck: &Deck, hand_size: usize, target: HandTypes) -> Vec<Impact> {
+    let mut impacts = impacts(deck, hand_size, &Change::candidates(deck));
+    impacts.sort_by(|a, b| b.delta(target).total_cmp(&a.delta(target)));
+    impacts
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        card::Card,
+        deck::Deck,
+        suit::{Ranks, Suits},
+        valid_hands::HandTypes,
+    };
+
+    use super::{impacts, ranked, Change};
+
+    #[test]
+    fn lists_every_change() {
+        let deck = Deck::standard();
+        let changes = Change::candidates(&deck);
+        assert_eq!(changes.len(), 52 + 13 + 4 + 52);
+
+        let ace: Card = "AS".parse().unwrap();
+        assert_eq!(Change::Remove(ace).apply(&deck).cards.len(), 51);
+        assert_eq!(Change::Add(ace).apply(&deck).cards.len(), 53);
+        assert_eq!(
+            Change::RemoveRank(Ranks::Ace).to_string(),
+            "remove every Ace"
+        );
+        let hearts = Change::RemoveSuit(Suits::Hearts).apply(&deck);
+        assert!(hearts.cards.iter().all(|c| c.suit != Suits::Hearts));
+        assert_eq!(hearts.cards.len(), 39);
+    }
+
+    #[test]
+    fn thinning_a_suit_helps_flushes() {
+        // Twos to Sixes: small enough for exact odds to be quick, and still
+        // the same for every suit and every rank.
+        let mut deck = Deck::standard();
+        deck.cards.retain(|c| c.value <= 6);
+        let changes = [
+            Change::RemoveSuit(Suits::Clubs),
+            Change::RemoveRank(Ranks::Two),
+            Change::Remove("2C".parse().unwrap()),
+            Change::Add("2C".parse().unwrap()),
+        ];
+        let [suit, rank, card, added] = impacts(&deck, 8, &changes).try_into().unwrap();
+
+        // Three suits left make Flushes likelier, and a rank gone makes Pairs
+        // and the like likelier. Fewer cards of a suit, though, means fewer
+        // Flushes.
+        assert!(suit.delta(HandTypes::Flush) > 0.03);
+        assert!(rank.delta(HandTypes::ThreeOAK) > 0.);
+        assert!(rank.delta(HandTypes::Flush) < 0.);
+        // Every card of the deck is equally likely to be part of any hand
+        // type, the one Straight included, so taking one out doesn't change
+        // the odds.
+        for hand_type in HandTypes::ALL {
+            assert!(card.delta(hand_type).abs() < 1e-12, "{hand_type:?}");
+        }
+        // Adding a card breaks the symmetry.
+        assert!(added.delta(HandTypes::Flush) > 0.);
+
+        let ranking = ranked(&deck, 5, HandTypes::Flush);
+        assert_eq!(ranking.len(), 20 + 5 + 4 + 20);
+        assert!(
+            matches!(ranking[0].change, Change::RemoveSuit(_)),
+            "{:?}",
+            ranking[0]
+        );
+        assert!(ranking.is_sorted_by(|a, b| a.delta(HandTypes::Flush) >= b.delta(HandTypes::Flush)));
+    }
+}
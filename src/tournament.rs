@@ -0,0 +1,164 @@
+//! Policies played against each other on the same seeds. Every policy plays
+//! round i and run i from the same shuffle, so the comparisons between them
+//! are paired: wins are compared with McNemar's test and chips and antes with
+//! paired t-tests, which see through far more of the luck of the deal than
+//! comparing independent samples would.
+
+use crate::{
+    policy::Policy,
+    round::{play_rounds, RoundResult, RoundSetup, Summary},
+    run::{play_runs, RunResult},
+    seed::Seed,
+    stats::{mcnemar, paired_t, percentile, PairedTest},
+};
+
+/// One policy's games, in seed order.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub name: &'static str,
+    /// Single rounds against the setup's blind.
+    pub rounds: Vec<RoundResult>,
+    /// Whole runs, see [`crate::run`].
+    pub runs: Vec<RunResult>,
+}
+
+impl Entry {
+    /// Plays `games` rounds and `games` runs with the policy.
+    pub fn play<P: Policy + Clone + Sync>(
+        policy: &P,
+        setup: &RoundSetup,
+        games: usize,
+        seed: &Seed,
+        threads: usize,
+    ) -> Entry {
+        Entry {
+            name: policy.name(),
+            rounds: play_rounds(setup, policy, games, seed, threads),
+            runs: play_runs(setup, policy, games, seed, threads),
+        }
+    }
+}
+
+/// How one policy did on its own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub name: &'static str,
+    pub rounds: Summary,
+    /// The 10th, 25th, 50th, 75th and 90th percentiles of chips per round.
+    pub chips_percentiles: [f64; 5],
+    pub mean_ante: f64,
+    pub mean_blinds: f64,
+}
+
+impl Standing {
+    pub fn new(entry: &Entry) -> Standing {
+        let mut chips: Vec<f64> = entry.rounds.iter().map(|r| r.chips).collect();
+        chips.sort_by(f64::total_cmp);
+        let runs = entry.runs.len().max(1) as f64;
+        Standing {
+            name: entry.name,
+            rounds: Summary::new(&entry.rounds),
+            chips_percentiles: [0.1, 0.25, 0.5, 0.75, 0.9].map(|q| percentile(&chips, q)),
+            mean_ante: entry.runs.iter().map(|r| f64::from(r.ante)).sum::<f64>() / runs,
+            mean_blinds: entry
+                .runs
+                .iter()
+                .map(|r| f64::from(r.blinds_beaten))
+                .sum::<f64>()
+                / runs,
+        }
+    }
+}
+
+/// One policy against another on the seeds both played. Differences are the
+/// first's results minus the second's.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub first: &'static str,
+    pub second: &'static str,
+    /// Rounds only the first won.
+    pub only_first: usize,
+    /// Rounds only the second won.
+    pub only_second: usize,
+    /// McNemar's test on the rounds only one of them won.
+    pub wins_p: f64,
+    pub chips: PairedTest,
+    pub ante: PairedTest,
+}
+
+impl Comparison {
+    pub fn new(first: &Entry, second: &Entry) -> Comparison {
+        let rounds = || first.rounds.iter().zip(&second.rounds);
+        let only = |a: bool, b: bool| rounds().filter(|(x, y)| x.won == a && y.won == b).count();
+        let (only_first, only_second) = (only(true, false), only(false, true));
+
+        let chips: Vec<f64> = rounds().map(|(x, y)| x.chips - y.chips).collect();
+        let antes: Vec<f64> = first
+            .runs
+            .iter()
+            .zip(&second.runs)
+            .map(|(x, y)| f64::from(x.ante) - f64::from(y.ante))
+            .collect();
+        Comparison {
+            first: first.name,
+            second: second.name,
+            only_first,
+            only_second,
+            wins_p: mcnemar(only_first, only_second),
+            chips: paired_t(&chips),
+            ante: paired_t(&antes),
+        }
+    }
+}
+
+/// Every pair of entries, each once, in the order given.
+pub fn compare_all(entries: &[Entry]) -> Vec<Comparison> {
+    let mut comparisons = vec![];
+    for (i, first) in entries.iter().enumerate() {
+        for second in &entries[i + 1..] {
+            comparisons.push(Comparison::new(first, second));
+        }
+    }
+    comparisons
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        deck::Deck,
+        policy::{ChaseFlush, Greedy},
+        round::RoundSetup,
+        seed::Seed,
+    };
+
+    use super::{compare_all, Entry, Standing};
+
+    #[test]
+    fn pairs_policies_on_the_same_seeds() {
+        let setup = RoundSetup::new(Deck::standard(), 300.);
+        let seed = Seed::Numeric(3);
+        let greedy = Entry::play(&Greedy, &setup, 60, &seed, 2);
+        let entries = [
+            greedy.clone(),
+            Entry::play(&ChaseFlush, &setup, 60, &seed, 2),
+            greedy,
+        ];
+
+        let standing = Standing::new(&entries[0]);
+        assert_eq!(standing.rounds.rounds, 60);
+        assert!(standing.chips_percentiles.is_sorted());
+        assert!(standing.mean_ante >= 1.);
+
+        let comparisons = compare_all(&entries);
+        assert_eq!(comparisons.len(), 3);
+        let (flush, same) = (&comparisons[0], &comparisons[1]);
+        // Chasing Flushes wins clearly more rounds than never discarding.
+        assert!(flush.only_second > flush.only_first, "{flush:?}");
+        assert!(flush.wins_p < 0.01, "{flush:?}");
+
+        // A policy against itself differs on nothing.
+        assert_eq!(same.first, same.second);
+        assert_eq!((same.only_first, same.only_second), (0, 0));
+        assert_eq!((same.wins_p, same.chips.p, same.ante.p), (1., 1., 1.));
+    }
+}
@@ -0,0 +1,457 @@
+//! Rounds played by hand in the terminal, for practising against the
+//! heuristics the solver and policies use. Needs the `tui` feature.
+//!
+//! The held cards are shown with their suits; pick cards with the arrow keys
+//! and space (or their number), then play or discard them. The picked cards'
+//! detected hands and score are shown as they're picked, and the solver can be
+//! asked for a hint at any point.
+
+use std::io;
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    card::Card,
+    deck::Deck,
+    hand::Hand,
+    policy::best_play,
+    round::{Action, Round, RoundSetup},
+    score::{score_with, Score},
+    seed::Seed,
+    simulation::{chunk_rng, SimConfig},
+    solve::{self, Objectives},
+    suit::{Ranks, Suits},
+    valid_hands::ValidHands,
+};
+
+/// Redraws per discard option when asking the solver for a hint.
+const HINT_SAMPLES: usize = 500;
+
+/// The state of a game in the terminal, separate from drawing it so the key
+/// handling can be tested without one.
+pub struct App {
+    setup: RoundSetup,
+    seed: Seed,
+    /// Which game of the seed is being played, as numbered by `play`.
+    game: usize,
+    round: Round,
+    /// Positions in the hand, in the order they were picked.
+    picked: Vec<usize>,
+    cursor: usize,
+    last_play: Option<Score>,
+    message: String,
+    hint: Option<String>,
+    wins: usize,
+    losses: usize,
+    pub quit: bool,
+}
+
+impl App {
+    pub fn new(setup: RoundSetup, seed: Seed) -> App {
+        let round = setup.deal(&mut chunk_rng(seed.to_u64(), 0));
+        App {
+            setup,
+            seed,
+            game: 0,
+            round,
+            picked: vec![],
+            cursor: 0,
+            last_play: None,
+            message: "Pick cards to play or discard".to_string(),
+            hint: None,
+            wins: 0,
+            losses: 0,
+            quit: false,
+        }
+    }
+
+    pub fn round(&self) -> &Round {
+        &self.round
+    }
+
+    pub fn picked(&self) -> &[usize] {
+        &self.picked
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn hint(&self) -> Option<&str> {
+        self.hint.as_deref()
+    }
+
+    pub fn handle(&mut self, key: KeyCode) {
+        let held = self.round.hand().len();
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(held.saturating_sub(1)),
+            KeyCode::Char(' ') => self.toggle(self.cursor),
+            KeyCode::Char(digit @ '1'..='9') => self.toggle(digit as usize - '1' as usize),
+            KeyCode::Char('c') => self.picked.clear(),
+            KeyCode::Char('p') | KeyCode::Enter => self.act(Action::Play(self.picked.clone())),
+            KeyCode::Char('d') => self.act(Action::Discard(self.picked.clone())),
+            KeyCode::Char('h') => self.ask_for_hint(),
+            KeyCode::Char('n') if self.round.is_over() => self.next_round(),
+            _ => {}
+        }
+    }
+
+    fn toggle(&mut self, position: usize) {
+        if self.round.is_over() || position >= self.round.hand().len() {
+            return;
+        }
+        match self.picked.iter().position(|p| *p == position) {
+            Some(i) => {
+                self.picked.remove(i);
+            }
+            None => self.picked.push(position),
+        }
+        self.cursor = position;
+    }
+
+    fn act(&mut self, action: Action) {
+        match self.round.apply(&action) {
+            Ok(score) => {
+                self.message = match &score {
+                    Some(score) => {
+                        format!("Played {} for {}", score.hand_type.to_str(), score.total())
+                    }
+                    None => "Discarded".to_string(),
+                };
+                self.last_play = score.or(self.last_play.take());
+                self.picked.clear();
+                self.hint = None;
+                self.cursor = self.cursor.min(self.round.hand().len().saturating_sub(1));
+            }
+            Err(e) => self.message = format!("Can't do that: {e}"),
+        }
+        if self.round.is_over() {
+            let won = self.round.won();
+            if won {
+                self.wins += 1;
+            } else {
+                self.losses += 1;
+            }
+            self.message = format!(
+                "{} with {} chips. Press n for the next round",
+                if won { "Won" } else { "Lost" },
+                self.round.chips()
+            );
+        }
+    }
+
+    // The best play now and the solver's best discard.
+    fn ask_for_hint(&mut self) {
+        if self.round.is_over() {
+            return;
+        }
+        let view = self.round.view();
+        let (play, score) = best_play(&view);
+        let play: Vec<Card> = play.iter().map(|i| view.hand[*i]).collect();
+        let mut hint = format!(
+            "Best play now: {} ({}, {})",
+            notation(&play),
+            score.hand_type.to_str(),
+            score.total()
+        );
+
+        if view.discards_left > 0 {
+            let mut config = SimConfig::new(HINT_SAMPLES, view.hand.len());
+            config.deck = Deck {
+                cards: view.remaining.clone(),
+            };
+            config.seed = self.seed.clone();
+            config.threads = 1;
+            let ranked = solve::recommend(view.hand, Objectives::Score, &config);
+            if let Some(best) = ranked.ok().and_then(|ranked| ranked.into_iter().next()) {
+                let discard = match best.discard.is_empty() {
+                    true => "nothing".to_string(),
+                    false => notation(&best.discard),
+                };
+                hint += &format!(
+                    ". Best discard: {discard} ({:.1} expected base score)",
+                    best.value
+                );
+            }
+        }
+        self.hint = Some(hint);
+    }
+
+    fn next_round(&mut self) {
+        self.game += 1;
+        self.round = self
+            .setup
+            .deal(&mut chunk_rng(self.seed.to_u64(), self.game));
+        self.picked.clear();
+        self.cursor = 0;
+        self.last_play = None;
+        self.hint = None;
+        self.message = format!("Round {}", self.game + 1);
+    }
+
+    /// The score of the picked cards if they were played now, and every hand
+    /// the detectors find in them.
+    pub fn preview(&self) -> Option<(Score, Vec<ValidHands>)> {
+        if self.picked.is_empty() {
+            return None;
+        }
+        let mut positions = self.picked.clone();
+        positions.sort_unstable();
+        let cards: Vec<Card> = positions.iter().map(|i| self.round.hand()[*i]).collect();
+        let view = self.round.view();
+        let score = score_with(&cards, view.jokers, view.levels);
+        let size = cards.len();
+        let detected = Hand { cards, size }.contains();
+        Some((score, detected))
+    }
+}
+
+fn notation(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(Card::to_notation)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn valid_cards(hand: &ValidHands) -> Vec<Card> {
+    match hand.clone() {
+        ValidHands::Pair(a, b) => vec![a, b],
+        ValidHands::ThreeOAK(a, b, c) => vec![a, b, c],
+        ValidHands::TwoPair(a, b, c, d) | ValidHands::FourOAK(a, b, c, d) => vec![a, b, c, d],
+        ValidHands::Straight(a, b, c, d, e)
+        | ValidHands::Flush(a, b, c, d, e)
+        | ValidHands::FullHouse(a, b, c, d, e)
+        | ValidHands::StraightFlush(a, b, c, d, e) => vec![a, b, c, d, e],
+    }
+}
+
+// "K♥" and the card's colour, with any enhancement, edition and seal after.
+fn card_span(card: &Card) -> Span<'static> {
+    let suit = match card.suit {
+        Suits::Spades => "♠",
+        Suits::Hearts => "♥",
+        Suits::Clubs => "♣",
+        Suits::Diamonds => "♦",
+        Suits::Blank => "",
+    };
+    let rank = match card.rank {
+        Ranks::Ace => "A",
+        Ranks::King => "K",
+        Ranks::Queen => "Q",
+        Ranks::Jack => "J",
+        Ranks::Blank => "",
+        rank => rank.to_str(),
+    };
+    let notation = card.to_notation();
+    let extras = notation.split_once('+').map_or("", |(_, extras)| extras);
+    let text = match extras {
+        "" => format!(" {rank}{suit} "),
+        extras => format!(" {rank}{suit} {} ", extras.replace('+', " ")),
+    };
+    let color = match card.suit {
+        Suits::Hearts | Suits::Diamonds => Color::Red,
+        Suits::Spades | Suits::Clubs => Color::White,
+        Suits::Blank => Color::Gray,
+    };
+    Span::styled(text, Style::default().fg(color))
+}
+
+fn draw(frame: &mut Frame, app: &App) {
+    let [status, hand, preview, hint, help] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Length(4),
+        Constraint::Min(6),
+        Constraint::Length(4),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let view = app.round.view();
+    let status_line = Line::from(format!(
+        "Chips {} / {}   Hands {}   Discards {}   Deck {}   Won {} Lost {}",
+        view.chips,
+        view.blind,
+        view.hands_left,
+        view.discards_left,
+        view.remaining.len(),
+        app.wins,
+        app.losses,
+    ));
+    frame.render_widget(
+        Paragraph::new(vec![status_line, Line::from(app.message.as_str()).bold()])
+            .block(Block::bordered().title(format!(" Seed {} ", app.seed))),
+        status,
+    );
+
+    let mut numbers = vec![];
+    let mut cards = vec![];
+    for (i, card) in view.hand.iter().enumerate() {
+        let mut span = card_span(card);
+        if app.picked.contains(&i) {
+            span = span.add_modifier(Modifier::REVERSED);
+        }
+        if i == app.cursor {
+            span = span.add_modifier(Modifier::UNDERLINED | Modifier::BOLD);
+        }
+        let width = span.content.chars().count();
+        numbers.push(Span::raw(format!("{:^width$}", i + 1)).dim());
+        cards.push(span);
+    }
+    frame.render_widget(
+        Paragraph::new(vec![Line::from(numbers), Line::from(cards)])
+            .block(Block::bordered().title(" Hand ")),
+        hand,
+    );
+
+    let mut lines = vec![];
+    match app.preview() {
+        Some((score, detected)) => {
+            lines.push(Line::from(format!(
+                "{} (level {}): {} chips x {} mult = {}",
+                score.hand_type.to_str(),
+                view.levels.level(score.hand_type),
+                score.chips,
+                score.mult,
+                score.total()
+            )));
+            lines.push(Line::from(format!("Scoring: {}", notation(&score.scoring))));
+            for hand in detected {
+                lines.push(Line::from(format!(
+                    "Contains {}: {}",
+                    hand.hand_type().to_str(),
+                    notation(&valid_cards(&hand))
+                )));
+            }
+        }
+        None => lines.push(Line::from("No cards picked").dim()),
+    }
+    if let Some(last) = &app.last_play {
+        lines.push(Line::from(format!(
+            "Last play: {} {} x {} = {}",
+            last.hand_type.to_str(),
+            last.chips,
+            last.mult,
+            last.total()
+        )));
+    }
+    if !view.jokers.is_empty() {
+        let jokers: Vec<String> = view.jokers.iter().map(|j| j.to_notation()).collect();
+        lines.push(Line::from(format!("Jokers: {}", jokers.join(", "))));
+    }
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Picked ")),
+        preview,
+    );
+
+    frame.render_widget(
+        Paragraph::new(app.hint.as_deref().unwrap_or("Press h for a hint"))
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().title(" Hint ")),
+        hint,
+    );
+    frame.render_widget(
+        Line::from("←/→ move  space/1-9 pick  c clear  p play  d discard  h hint  n next  q quit")
+            .dim(),
+        help,
+    );
+}
+
+/// Plays rounds of the setup in the terminal until the player quits. Round i
+/// is dealt the same cards as game i of `play` with the same seed.
+pub fn run(setup: RoundSetup, seed: Seed) -> io::Result<()> {
+    let mut app = App::new(setup, seed);
+    let mut terminal = ratatui::init();
+    let result = (|| {
+        while !app.quit {
+            terminal.draw(|frame| draw(frame, &app))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    app.handle(key.code);
+                }
+            }
+        }
+        Ok(())
+    })();
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{backend::TestBackend, crossterm::event::KeyCode, Terminal};
+
+    use crate::{deck::Deck, round::RoundSetup, seed::Seed, valid_hands::HandTypes};
+
+    use super::{draw, App};
+
+    fn app() -> App {
+        let mut setup = RoundSetup::new(Deck::standard(), 1e9);
+        setup.held = "AS AH KD 7C 2S"
+            .split(' ')
+            .map(|c| c.parse().unwrap())
+            .collect();
+        setup.hands = 2;
+        App::new(setup, Seed::Numeric(1))
+    }
+
+    #[test]
+    fn picks_previews_and_plays() {
+        let mut app = app();
+        for key in ['1', '2', '3', '3'] {
+            app.handle(KeyCode::Char(key));
+        }
+        assert_eq!(app.picked(), [0, 1]);
+        let (score, detected) = app.preview().unwrap();
+        assert_eq!(score.hand_type, HandTypes::Pair);
+        assert_eq!(detected[0].hand_type(), HandTypes::Pair);
+
+        app.handle(KeyCode::Char('p'));
+        assert!(app.picked().is_empty());
+        assert_eq!(app.round().view().hands_left, 1);
+        assert_eq!(app.round().hand().len(), 8);
+
+        // Nothing picked is refused and changes nothing.
+        app.handle(KeyCode::Char('d'));
+        assert!(app.message().starts_with("Can't"), "{}", app.message());
+        assert_eq!(app.round().view().discards_left, 3);
+
+        app.handle(KeyCode::Char('h'));
+        assert!(app.hint().unwrap().contains("Best discard"));
+
+        app.handle(KeyCode::Char('1'));
+        app.handle(KeyCode::Enter);
+        assert!(app.round().is_over());
+        app.handle(KeyCode::Char('n'));
+        assert_eq!(app.round().view().hands_left, 2);
+        app.handle(KeyCode::Char('q'));
+        assert!(app.quit);
+    }
+
+    #[test]
+    fn draws_the_hand_with_suits() {
+        let mut app = app();
+        app.handle(KeyCode::Char('1'));
+        let mut terminal = Terminal::new(TestBackend::new(90, 24)).unwrap();
+        terminal.draw(|frame| draw(frame, &app)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        for text in ["A♠", "A♥", "K♦", "High Card (level 1)", "Pick cards"] {
+            assert!(screen.contains(text), "{text} missing");
+        }
+    }
+}
@@ -1,15 +1,14 @@
-use std::{
-    collections::{hash_set, HashSet},
-    fmt,
-};
+use std::{error::Error, fmt, str::FromStr};
 
 use crate::{
-    card::{self, Card},
+    card::Card,
     hand::Hand,
-    suit::Ranks,
 };
 
+/// A detected hand along with the cards that make it up. The `has_*` and
+/// `detect_*` functions return the hand and the cards left over.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ValidHands {
     Pair(Card, Card),
     TwoPair(Card, Card, Card, Card),
@@ -21,6 +20,104 @@ pub enum ValidHands {
     StraightFlush(Card, Card, Card, Card, Card),
 }
 
+/// Card-free label for each hand category, ordered from weakest to strongest.
+/// HighCard stands in for "none of the detectors fired".
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HandTypes {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOAK,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOAK,
+    StraightFlush,
+}
+
+impl HandTypes {
+    pub const ALL: [HandTypes; 9] = [
+        HandTypes::HighCard,
+        HandTypes::Pair,
+        HandTypes::TwoPair,
+        HandTypes::ThreeOAK,
+        HandTypes::Straight,
+        HandTypes::Flush,
+        HandTypes::FullHouse,
+        HandTypes::FourOAK,
+        HandTypes::StraightFlush,
+    ];
+
+    pub fn to_str(self) -> &'static str {
+        match self {
+            HandTypes::HighCard => "High Card",
+            HandTypes::Pair => "Pair",
+            HandTypes::TwoPair => "Two Pair",
+            HandTypes::ThreeOAK => "Three OAK",
+            HandTypes::Straight => "Straight",
+            HandTypes::Flush => "Flush",
+            HandTypes::FullHouse => "Full House",
+            HandTypes::FourOAK => "Four OAK",
+            HandTypes::StraightFlush => "Straight Flush",
+        }
+    }
+
+    /// The highest ranked type the hand contains.
+    pub fn best(hand: &Hand) -> HandTypes {
+        HandTypes::ALL
+            .into_iter()
+            .rev()
+            .find(|t| t.detect(hand))
+            .unwrap_or(HandTypes::HighCard)
+    }
+
+    pub fn detect(self, hand: &Hand) -> bool {
+        match self {
+            HandTypes::HighCard => {
+                !hand.cards.is_empty() && HandTypes::ALL[1..].iter().all(|t| !t.detect(hand))
+            }
+            HandTypes::Pair => ValidHands::has_n_of_a_kind(hand.clone(), 2).is_some(),
+            HandTypes::TwoPair => ValidHands::has_two_pair(hand.clone()).is_some(),
+            HandTypes::ThreeOAK => ValidHands::has_n_of_a_kind(hand.clone(), 3).is_some(),
+            HandTypes::Straight => ValidHands::has_straight(hand.clone()).is_some(),
+            HandTypes::Flush => ValidHands::detect_flush(hand.clone()).is_some(),
+            HandTypes::FullHouse => ValidHands::has_full_house(hand.clone()).is_some(),
+            HandTypes::FourOAK => ValidHands::has_n_of_a_kind(hand.clone(), 4).is_some(),
+            HandTypes::StraightFlush => ValidHands::has_straight_flush(hand.clone()).is_some(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseHandTypeError(String);
+
+impl fmt::Display for ParseHandTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown hand type {:?}", self.0)
+    }
+}
+
+impl Error for ParseHandTypeError {}
+
+// Accepts the names from to_str in any case, with spaces, dashes or
+// underscores between words, e.g. "full-house" or "Three OAK".
+impl FromStr for HandTypes {
+    type Err = ParseHandTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '_'))
+            .collect::<String>()
+            .to_ascii_lowercase();
+        HandTypes::ALL
+            .into_iter()
+            .find(|t| t.to_str().replace(' ', "").to_ascii_lowercase() == name)
+            .ok_or_else(|| ParseHandTypeError(s.to_string()))
+    }
+}
+
 impl fmt::Display for ValidHands {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -55,10 +152,22 @@ impl fmt::Display for ValidHands {
 }
 
 impl ValidHands {
+    pub fn hand_type(&self) -> HandTypes {
+        match self {
+            ValidHands::Pair(..) => HandTypes::Pair,
+            ValidHands::TwoPair(..) => HandTypes::TwoPair,
+            ValidHands::ThreeOAK(..) => HandTypes::ThreeOAK,
+            ValidHands::Straight(..) => HandTypes::Straight,
+            ValidHands::Flush(..) => HandTypes::Flush,
+            ValidHands::FullHouse(..) => HandTypes::FullHouse,
+            ValidHands::FourOAK(..) => HandTypes::FourOAK,
+            ValidHands::StraightFlush(..) => HandTypes::StraightFlush,
+        }
+    }
+
     pub fn has_n_of_a_kind(hand: Hand, n: usize) -> Option<(ValidHands, Hand)> {
         let mut detected_n_oak = None;
         let rle = hand.get_run_length_hashmap();
-        println!("{rle:?}");
         for (_card_value, amount_in_hand) in rle {
             if amount_in_hand == n {
                 match n {
@@ -98,21 +207,18 @@ impl ValidHands {
         let mut pair_b = false;
 
         for (_, v) in rle {
-            if pair_a == false && v >= 2 {
+            if !pair_a && v >= 2 {
                 pair_a = true
-            } else if pair_a == true && v >= 2 {
+            } else if pair_a && v >= 2 {
                 pair_b = true
             }
         }
 
-        match (pair_a, pair_b) {
-            (true, true) => {
-                detected_two_pair = Some((
-                    ValidHands::TwoPair(Card::blank(), Card::blank(), Card::blank(), Card::blank()),
-                    hand,
-                ))
-            }
-            _ => (),
+        if let (true, true) = (pair_a, pair_b) {
+            detected_two_pair = Some((
+                ValidHands::TwoPair(Card::blank(), Card::blank(), Card::blank(), Card::blank()),
+                hand,
+            ))
         }
 
         detected_two_pair
@@ -387,11 +493,11 @@ mod tests {
             ValidHands::detect_flush(hand.clone()),
             Some((
                 ValidHands::Flush(
-                    hand.cards[0].clone(),
-                    hand.cards[1].clone(),
-                    hand.cards[2].clone(),
-                    hand.cards[3].clone(),
-                    hand.cards[4].clone(),
+                    hand.cards[0],
+                    hand.cards[1],
+                    hand.cards[2],
+                    hand.cards[3],
+                    hand.cards[4],
                 ),
                 hand
             ))
@@ -429,6 +535,20 @@ mod tests {
                 ],
                 size: 8,
             },
+            // this case fails if the highest card value is duplicated and dropped
+            Hand {
+                cards: vec![
+                    Card::from_card_value(2, None),
+                    Card::from_card_value(3, None),
+                    Card::from_card_value(4, None),
+                    Card::from_card_value(5, None),
+                    Card::from_card_value(6, None),
+                    Card::from_card_value(6, None),
+                    Card::from_card_value(6, None),
+                    Card::from_card_value(6, None),
+                ],
+                size: 8,
+            },
             // this case fails if ace-high is not detected as end of straight
             Hand {
                 cards: vec![
@@ -463,6 +583,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ignores_non_straights() {
+        let hands = vec![
+            // this case fails if a second Ace is treated as a new value when Ace-Low
+            Hand {
+                cards: vec![
+                    Card::from_card_value(14, Some(Suits::Hearts)),
+                    Card::from_card_value(14, Some(Suits::Clubs)),
+                    Card::from_card_value(2, None),
+                    Card::from_card_value(3, None),
+                    Card::from_card_value(4, None),
+                    Card::from_card_value(7, None),
+                    Card::from_card_value(8, None),
+                    Card::from_card_value(10, None),
+                ],
+                size: 8,
+            },
+        ];
+        for (idx, hand) in hands.into_iter().enumerate() {
+            assert_eq!(
+                ValidHands::has_straight(hand),
+                None,
+                "Failed on case {}",
+                idx
+            );
+        }
+    }
+
     #[test]
     fn finds_full_house() {
         let hands = vec![
@@ -564,4 +712,16 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn parses_hand_type_names() {
+        use crate::valid_hands::HandTypes;
+
+        for hand_type in HandTypes::ALL {
+            assert_eq!(hand_type.to_str().parse(), Ok(hand_type));
+        }
+        assert_eq!("full-house".parse(), Ok(HandTypes::FullHouse));
+        assert_eq!("STRAIGHT_FLUSH".parse(), Ok(HandTypes::StraightFlush));
+        assert!("five of a kind".parse::<HandTypes>().is_err());
+    }
 }
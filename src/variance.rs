@@ -0,0 +1,305 @@
+//! Lower variance estimators for hand frequencies on a standard 52 card deck.
+//!
+//! Stratified sampling splits draws by how many cards of each suit they hold.
+//! The probability of each suit split is known exactly, so only the behaviour
+//! within a split has to be sampled, and hands decided entirely by suits (flushes)
+//! come out with no sampling error at all.
+//!
+//! Importance sampling targets the rare hands directly: half the draws have a
+//! straight flush or four of a kind planted in them, and every draw is reweighted
+//! by the ratio of its true probability to the mixed proposal so the estimate
+//! stays unbiased.
+
+use rand::{seq::SliceRandom, Rng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    card::Card,
+    card_set::CardSet,
+    deck::Deck,
+    simulation::{run_chunks, run_indexed, SimConfig, Tally},
+    stats::choose,
+    suit::Suits,
+    valid_hands::HandTypes,
+};
+
+const DECK_SIZE: usize = 52;
+const SUITS: [Suits; 4] = [Suits::Spades, Suits::Hearts, Suits::Clubs, Suits::Diamonds];
+const PLANT_PROBABILITY: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub std_error: f64,
+    /// Draws actually made, which the report shows next to those asked for.
+    pub samples: usize,
+}
+
+fn suit_splits(hand_size: usize) -> Vec<[usize; 4]> {
+    let mut splits = vec![];
+    for spades in 0..=hand_size.min(13) {
+        for hearts in 0..=(hand_size - spades).min(13) {
+            for clubs in 0..=(hand_size - spades - hearts).min(13) {
+                let diamonds = hand_size - spades - hearts - clubs;
+                if diamonds <= 13 {
+                    splits.push([spades, hearts, clubs, diamonds]);
+                }
+            }
+        }
+    }
+    splits
+}
+
+// Proportional allocation, with at least two draws per stratum so every
+// stratum has a variance estimate. What rounding and the minimum add or leave
+// over is evened out on the largest strata, so the total is exactly `samples`
+// unless that is fewer than two per stratum.
+fn allocate(samples: usize, weights: &[f64]) -> Vec<usize> {
+    let mut allocation: Vec<usize> = weights
+        .iter()
+        .map(|weight| ((samples as f64 * weight).round() as usize).max(2))
+        .collect();
+    let mut total: usize = allocation.iter().sum();
+    while total != samples {
+        let largest = (0..allocation.len())
+            .max_by_key(|i| allocation[*i])
+            .expect("there is always a suit split");
+        if total < samples {
+            allocation[largest] += 1;
+            total += 1;
+        } else if allocation[largest] > 2 {
+            allocation[largest] -= 1;
+            total -= 1;
+        } else {
+            break;
+        }
+    }
+    allocation
+}
+
+/// Estimates from `config.samples` draws, or two per suit split if that is
+/// more (165 splits for an eight card hand).
+pub fn stratified(config: &SimConfig) -> Vec<(HandTypes, Estimate)> {
+    let deck = Deck::standard();
+    let by_suit: Vec<Vec<Card>> = SUITS
+        .iter()
+        .map(|suit| {
+            deck.cards
+                .iter()
+                .filter(|c| c.suit == *suit)
+                .copied()
+                .collect()
+        })
+        .collect();
+
+    let total = choose(DECK_SIZE, config.hand_size);
+    let strata: Vec<([usize; 4], f64)> = suit_splits(config.hand_size)
+        .into_iter()
+        .map(|split| {
+            let ways: f64 = split.iter().map(|n| choose(13, *n)).product();
+            (split, ways / total)
+        })
+        .collect();
+
+    let weights: Vec<f64> = strata.iter().map(|(_, weight)| *weight).collect();
+    let allocation = allocate(config.samples, &weights);
+
+    let tallies = run_indexed(
+        0..strata.len(),
+        config.threads,
+        config.seed.to_u64(),
+        &|rng: &mut ChaCha8Rng, stratum| {
+            let (split, _) = strata[stratum];
+            let mut by_suit = by_suit.clone();
+            let mut tally = Tally::default();
+            for _ in 0..allocation[stratum] {
+                let mut cards = CardSet::new();
+                for (suit_cards, n) in by_suit.iter_mut().zip(split) {
+                    let (drawn, _) = suit_cards.partial_shuffle(rng, n);
+                    cards = cards.union(CardSet::from_cards(drawn));
+                }
+                tally.record_set(cards);
+            }
+            tally
+        },
+    );
+
+    HandTypes::ALL
+        .iter()
+        .map(|hand_type| {
+            let mut mean = 0.;
+            let mut variance = 0.;
+            for ((_, weight), tally) in strata.iter().zip(&tallies) {
+                let n = tally.samples as f64;
+                let p = tally.frequency(*hand_type);
+                mean += weight * p;
+                variance += weight * weight * p * (1. - p) / (n - 1.);
+            }
+            let estimate = Estimate {
+                mean,
+                std_error: variance.sqrt(),
+                samples: allocation.iter().sum(),
+            };
+            (*hand_type, estimate)
+        })
+        .collect()
+}
+
+// Card sets whose presence guarantees the hand type, used as planting targets.
+fn planted_targets(hand_type: HandTypes) -> Option<Vec<CardSet>> {
+    match hand_type {
+        HandTypes::StraightFlush => {
+            let mut targets = vec![];
+            for suit in SUITS {
+                // Lowest card value 1 is the ace-low straight.
+                for low in 1..=10 {
+                    let cards: Vec<Card> = (low..low + 5)
+                        .map(|value| Card::from_card_value(value, Some(suit)))
+                        .collect();
+                    targets.push(CardSet::from_cards(&cards));
+                }
+            }
+            Some(targets)
+        }
+        HandTypes::FourOAK => Some(
+            (2..=14)
+                .map(|value| {
+                    CardSet::from_cards(&SUITS.map(|suit| Card::from_card_value(value, Some(suit))))
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Returns None for hand types without planting targets; they are common enough
+/// that plain or stratified sampling does fine.
+pub fn importance(config: &SimConfig, hand_type: HandTypes) -> Option<Estimate> {
+    let targets = planted_targets(hand_type)?;
+    let target_size = targets[0].len();
+    let hand_size = config.hand_size;
+    if hand_size < target_size {
+        return Some(Estimate {
+            mean: 0.,
+            std_error: 0.,
+            samples: 0,
+        });
+    }
+
+    let deck = Deck::standard();
+    // Probability of a specific hand under the planted proposal, relative to
+    // uniform, per target it contains.
+    let planted_ratio = choose(DECK_SIZE, hand_size)
+        / (targets.len() as f64 * choose(DECK_SIZE - target_size, hand_size - target_size));
+
+    let sums = run_chunks(
+        config.samples,
+        config.threads,
+        config.seed.to_u64(),
+        |rng, n| {
+            let mut shuffled = deck.cards.clone();
+            let (mut sum, mut sum_sq) = (0., 0.);
+            for _ in 0..n {
+                let cards = if rng.gen_bool(PLANT_PROBABILITY) {
+                    let target = targets[rng.gen_range(0..targets.len())];
+                    let rest: Vec<Card> = deck
+                        .cards
+                        .iter()
+                        .filter(|c| !target.contains(c))
+                        .copied()
+                        .collect();
+                    target.union(CardSet::from_cards(
+                        &rest
+                            .choose_multiple(rng, hand_size - target_size)
+                            .copied()
+                            .collect::<Vec<_>>(),
+                    ))
+                } else {
+                    let (drawn, _) = shuffled.partial_shuffle(rng, hand_size);
+                    CardSet::from_cards(drawn)
+                };
+
+                if cards.contains_type(hand_type) {
+                    let contained = targets.iter().filter(|t| cards.contains_all(**t)).count();
+                    let weight = 1.
+                        / ((1. - PLANT_PROBABILITY)
+                            + PLANT_PROBABILITY * planted_ratio * contained as f64);
+                    sum += weight;
+                    sum_sq += weight * weight;
+                }
+            }
+            (sum, sum_sq)
+        },
+    );
+
+    let n = config.samples as f64;
+    let (sum, sum_sq) = sums
+        .iter()
+        .fold((0., 0.), |(s, sq), (cs, csq)| (s + cs, sq + csq));
+    let mean = sum / n;
+    let variance = (sum_sq / n - mean * mean).max(0.) / (n - 1.);
+
+    Some(Estimate {
+        mean,
+        std_error: variance.sqrt(),
+        samples: config.samples,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{seed::Seed, simulation::SimConfig, stats::choose, valid_hands::HandTypes};
+
+    use super::{importance, stratified};
+
+    fn config(samples: usize, hand_size: usize) -> SimConfig {
+        let mut config = SimConfig::new(samples, hand_size);
+        config.seed = Seed::Numeric(11);
+        config
+    }
+
+    #[test]
+    fn stratified_flush_is_exact() {
+        let estimates = stratified(&config(2000, 5));
+        let (_, flush) = estimates
+            .iter()
+            .find(|(t, _)| *t == HandTypes::Flush)
+            .unwrap();
+
+        assert!((flush.mean - 5148. / choose(52, 5)).abs() < 1e-12);
+        assert_eq!(flush.std_error, 0.);
+    }
+
+    #[test]
+    fn stratified_draws_what_was_asked() {
+        for samples in [2000, 2001, 12_345] {
+            let estimates = stratified(&config(samples, 8));
+            assert!(estimates.iter().all(|(_, e)| e.samples == samples));
+        }
+        // Too few to give every suit split its two draws.
+        let estimates = stratified(&config(100, 8));
+        assert!(estimates.iter().all(|(_, e)| e.samples == 2 * 165));
+    }
+
+    #[test]
+    fn importance_sampling_recovers_rare_hands() {
+        let total = choose(52, 5);
+        for (hand_type, exact) in [
+            (HandTypes::StraightFlush, 40. / total),
+            (HandTypes::FourOAK, 624. / total),
+        ] {
+            let estimate = importance(&config(4000, 5), hand_type).unwrap();
+            assert!(
+                (estimate.mean - exact).abs() < 4. * estimate.std_error,
+                "{hand_type:?}: {estimate:?} vs {exact}"
+            );
+            // Plain sampling would need millions of draws for this precision.
+            assert!(
+                estimate.std_error < exact * 0.1,
+                "{hand_type:?}: {estimate:?}"
+            );
+        }
+
+        assert!(importance(&config(10, 5), HandTypes::Pair).is_none());
+    }
+}
@@ -0,0 +1 @@
+return {["cardAreas"]={["jokers"]={["cards"]={[1]={["sort_id"]=1084,["save_fields"]={["center"]="j_lusty_joker",},["ability"]={["name"]="Lusty Joker",["set"]="Joker",["mult"]=0,["x_mult"]=1,["extra"]={["s_mult"]=3,["suit"]="Hearts",},},["label"]="Lusty Joker",["facing"]="front",["debuff"]=false,["cost"]=5,["sell_cost"]=2,},[2]={["sort_id"]=1091,["save_fields"]={["center"]="j_blueprint",},["ability"]={["name"]="Blueprint",["set"]="Joker",["mult"]=0,["x_mult"]=1,["extra"]=2,},["label"]="Blueprint",["facing"]="front",["debuff"]=false,["cost"]=5,["sell_cost"]=2,},[3]={["sort_id"]=1098,["save_fields"]={["center"]="j_tribe",},["ability"]={["name"]="The Tribe",["set"]="Joker",["mult"]=0,["x_mult"]=1,["extra"]=2,},["label"]="The Tribe",["facing"]="front",["debuff"]=false,["cost"]=5,["sell_cost"]=2,["edition"]={["foil"]=true,["chips"]=50,["type"]="foil",},},},["config"]={["card_limit"]=5,["type"]="joker",["highlighted_limit"]=5,["temp_limit"]=5,},},["deck"]={["cards"]={[5]={["sort_id"]=1077,["save_fields"]={["center"]="m_bonus",["card"]="H_Q",},["params"]={["playing_card"]=77,["bypass_discovery_center"]=true,},["base"]={["id"]=12,["suit"]="Hearts",["value"]="Queen",["nominal"]=10,["name"]="Queen of Hearts",["times_played"]=0,["original_value"]="Queen",},["ability"]={["name"]="m_bonus",["set"]="Default",["bonus"]=0,["perma_bonus"]=0,["mult"]=0,["x_mult"]=1,["extra"]={},},["label"]="Base Card",["facing"]="front",["sprite_facing"]="front",["debuff"]=false,["playing_card"]=77,["edition"]={["holo"]=true,["mult"]=10,["type"]="holo",},},[4]={["sort_id"]=1070,["save_fields"]={["center"]="m_wild",["card"]="H_J",},["params"]={["playing_card"]=70,["bypass_discovery_center"]=true,},["base"]={["id"]=11,["suit"]="Hearts",["value"]="Jack",["nominal"]=10,["name"]="Jack of Hearts",["times_played"]=0,["original_value"]="Jack",},["ability"]={["name"]="m_wild",["set"]="Default",["bonus"]=0,["perma_bonus"]=0,["mult"]=0,["x_mult"]=1,["extra"]={},},["label"]="Base Card",["facing"]="front",["sprite_facing"]="front",["debuff"]=false,["playing_card"]=70,},[3]={["sort_id"]=1063,["save_fields"]={["center"]="c_base",["card"]="S_5",},["params"]={["playing_card"]=63,["bypass_discovery_center"]=true,},["base"]={["id"]=5,["suit"]="Spades",["value"]="5",["nominal"]=5,["name"]="5 of Spades",["times_played"]=0,["original_value"]="5",},["ability"]={["name"]="Default Base",["set"]="Default",["bonus"]=0,["perma_bonus"]=0,["mult"]=0,["x_mult"]=1,["extra"]={},},["label"]="Base Card",["facing"]="front",["sprite_facing"]="front",["debuff"]=false,["playing_card"]=63,},[2]={["sort_id"]=1056,["save_fields"]={["center"]="m_steel",["card"]="D_9",},["params"]={["playing_card"]=56,["bypass_discovery_center"]=true,},["base"]={["id"]=9,["suit"]="Diamonds",["value"]="9",["nominal"]=9,["name"]="9 of Diamonds",["times_played"]=0,["original_value"]="9",},["ability"]={["name"]="m_steel",["set"]="Default",["bonus"]=0,["perma_bonus"]=0,["mult"]=0,["x_mult"]=1,["extra"]={},},["label"]="Base Card",["facing"]="front",["sprite_facing"]="front",["debuff"]=false,["playing_card"]=56,},[1]={["sort_id"]=1049,["save_fields"]={["center"]="c_base",["card"]="C_2",},["params"]={["playing_card"]=49,["bypass_discovery_center"]=true,},["base"]={["id"]=2,["suit"]="Clubs",["value"]="2",["nominal"]=2,["name"]="2 of Clubs",["times_played"]=0,["original_value"]="2",},["ability"]={["name"]="Default Base",["set"]="Default",["bonus"]=0,["perma_bonus"]=0,["mult"]=0,["x_mult"]=1,["extra"]={},},["label"]="Base Card",["facing"]="front",["sprite_facing"]="front",["debuff"]=false,["playing_card"]=49,},},["config"]={["card_limit"]=52,["type"]="deck",["highlighted_limit"]=5,["temp_limit"]=52,},},["hand"]={["cards"]={[1]={["sort_id"]=1007,["save_fields"]={["center"]="c_base",["card"]="H_A",},["params"]={["playing_card"]=7,["bypass_discovery_center"]=true,},["base"]={["id"]=14,["suit"]="Hearts",["value"]="Ace",["nominal"]=11,["name"]="Ace of Hearts",["times_played"]=0,["original_value"]="Ace",},["ability"]={["name"]="Default Base",["set"]="Default",["bonus"]=0,["perma_bonus"]=0,["mult"]=0,["x_mult"]=1,["extra"]={},},["label"]="Base Card",["facing"]="front",["sprite_facing"]="front",["debuff"]=false,["playing_card"]=7,},[2]={["sort_id"]=1014,["save_fields"]={["center"]="m_glass",["card"]="H_K",},["params"]={["playing_card"]=14,["bypass_discovery_center"]=true,},["base"]={["id"]=13,["suit"]="Hearts",["value"]="King",["nominal"]=10,["name"]="King of Hearts",["times_played"]=0,["original_value"]="King",},["ability"]={["name"]="m_glass",["set"]="Default",["bonus"]=0,["perma_bonus"]=0,["mult"]=0,["x_mult"]=1,["extra"]={},},["label"]="Base Card",["facing"]="front",["sprite_facing"]="front",["debuff"]=false,["playing_card"]=14,["seal"]="Red",},[3]={["sort_id"]=1021,["save_fields"]={["center"]="c_base",["card"]="H_T",},["params"]={["playing_card"]=21,["bypass_discovery_center"]=true,},["base"]={["id"]=10,["suit"]="Hearts",["value"]="10",["nominal"]=10,["name"]="10 of Hearts",["times_played"]=0,["original_value"]="10",},["ability"]={["name"]="Default Base",["set"]="Default",["bonus"]=0,["perma_bonus"]=0,["mult"]=0,["x_mult"]=1,["extra"]={},},["label"]="Base Card",["facing"]="front",["sprite_facing"]="front",["debuff"]=false,["playing_card"]=21,},[4]={["sort_id"]=1028,["save_fields"]={["center"]="c_base",["card"]="S_7",},["params"]={["playing_card"]=28,["bypass_discovery_center"]=true,},["base"]={["id"]=7,["suit"]="Spades",["value"]="7",["nominal"]=7,["name"]="7 of Spades",["times_played"]=0,["original_value"]="7",},["ability"]={["name"]="Default Base",["set"]="Default",["bonus"]=0,["perma_bonus"]=0,["mult"]=0,["x_mult"]=1,["extra"]={},},["label"]="Base Card",["facing"]="front",["sprite_facing"]="front",["debuff"]=false,["playing_card"]=28,["edition"]={["polychrome"]=true,["x_mult"]=1.5,},},[5]={["sort_id"]=1035,["save_fields"]={["center"]="c_base",["card"]="C_3",},["params"]={["playing_card"]=35,["bypass_discovery_center"]=true,},["base"]={["id"]=3,["suit"]="Clubs",["value"]="3",["nominal"]=3,["name"]="3 of Clubs",["times_played"]=0,["original_value"]="3",},["ability"]={["name"]="Default Base",["set"]="Default",["bonus"]=0,["perma_bonus"]=0,["mult"]=0,["x_mult"]=1,["extra"]={},},["label"]="Base Card",["facing"]="front",["sprite_facing"]="front",["debuff"]=false,["playing_card"]=35,},[6]={["sort_id"]=1042,["save_fields"]={["center"]="c_base",["card"]="D_3",},["params"]={["playing_card"]=42,["bypass_discovery_center"]=true,},["base"]={["id"]=3,["suit"]="Diamonds",["value"]="3",["nominal"]=3,["name"]="3 of Diamonds",["times_played"]=0,["original_value"]="3",},["ability"]={["name"]="Default Base",["set"]="Default",["bonus"]=0,["perma_bonus"]=0,["mult"]=0,["x_mult"]=1,["extra"]={},},["label"]="Base Card",["facing"]="front",["sprite_facing"]="front",["debuff"]=false,["playing_card"]=42,},},["config"]={["card_limit"]=8,["type"]="hand",["highlighted_limit"]=5,["temp_limit"]=8,},},["consumeables"]={["cards"]={},["config"]={["card_limit"]=2,["type"]="joker",["highlighted_limit"]=5,["temp_limit"]=2,},},["discard"]={["cards"]={[1]={["sort_id"]=1105,["save_fields"]={["center"]="c_base",["card"]="H_4",},["params"]={["playing_card"]=105,["bypass_discovery_center"]=true,},["base"]={["id"]=4,["suit"]="Hearts",["value"]="4",["nominal"]=4,["name"]="4 of Hearts",["times_played"]=0,["original_value"]="4",},["ability"]={["name"]="Default Base",["set"]="Default",["bonus"]=0,["perma_bonus"]=0,["mult"]=0,["x_mult"]=1,["extra"]={},},["label"]="Base Card",["facing"]="front",["sprite_facing"]="front",["debuff"]=false,["playing_card"]=105,},[2]={["sort_id"]=1112,["save_fields"]={["center"]="c_base",["card"]="C_8",},["params"]={["playing_card"]=112,["bypass_discovery_center"]=true,},["base"]={["id"]=8,["suit"]="Clubs",["value"]="8",["nominal"]=8,["name"]="8 of Clubs",["times_played"]=0,["original_value"]="8",},["ability"]={["name"]="Default Base",["set"]="Default",["bonus"]=0,["perma_bonus"]=0,["mult"]=0,["x_mult"]=1,["extra"]={},},["label"]="Base Card",["facing"]="front",["sprite_facing"]="front",["debuff"]=false,["playing_card"]=112,},},["config"]={["card_limit"]=500,["type"]="discard",["highlighted_limit"]=5,["temp_limit"]=500,},},},["GAME"]={["dollars"]=12,["round"]=7,["stake"]=1,["hands"]={["Flush Five"]={["level"]=1,["chips"]=10,["mult"]=1,["played"]=0,["visible"]=true,["order"]=1,},["Flush House"]={["level"]=1,["chips"]=10,["mult"]=1,["played"]=0,["visible"]=true,["order"]=2,},["Five of a Kind"]={["level"]=1,["chips"]=10,["mult"]=1,["played"]=0,["visible"]=true,["order"]=3,},["Straight Flush"]={["level"]=1,["chips"]=10,["mult"]=1,["played"]=0,["visible"]=true,["order"]=4,},["Four of a Kind"]={["level"]=1,["chips"]=10,["mult"]=1,["played"]=0,["visible"]=true,["order"]=5,},["Full House"]={["level"]=1,["chips"]=10,["mult"]=1,["played"]=0,["visible"]=true,["order"]=6,},["Flush"]={["level"]=3,["chips"]=10,["mult"]=1,["played"]=0,["visible"]=true,["order"]=7,},["Straight"]={["level"]=1,["chips"]=10,["mult"]=1,["played"]=0,["visible"]=true,["order"]=8,},["Three of a Kind"]={["level"]=2,["chips"]=10,["mult"]=1,["played"]=0,["visible"]=true,["order"]=9,},["Two Pair"]={["level"]=1,["chips"]=10,["mult"]=1,["played"]=0,["visible"]=true,["order"]=10,},["Pair"]={["level"]=1,["chips"]=10,["mult"]=1,["played"]=0,["visible"]=true,["order"]=11,},["High Card"]={["level"]=1,["chips"]=10,["mult"]=1,["played"]=0,["visible"]=true,["order"]=12,},},["current_round"]={["hands_left"]=3,["discards_left"]=1,["hands_played"]=1,["reroll_cost"]=5,},["round_resets"]={["hands"]=4,["discards"]=3,["ante"]=3,["blind"]="MANUAL_REPLACE",},["pseudorandom"]={["seed"]="7LB2WVPK",["hashed_seed"]=0.35618371843617,["boss"]=0.4213,},["blind"]="MANUAL_REPLACE",["modifiers"]={},},["BLIND"]={["name"]="The Hook",["chips"]=1400,["chip_text"]="1,400",["dollars"]=5,["mult"]=2,["disabled"]=false,["config_blind"]="bl_hook",},["STATE"]=2,["VERSION"]="1.0.1o-FULL",["ACHIEVEMENTS"]={},}
\ No newline at end of file